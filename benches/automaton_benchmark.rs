@@ -1,11 +1,14 @@
 use std::hint::black_box;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use cellular_automaton::automaton::Automaton;
+use cellular_automaton::bit_row::BitRow;
 use cellular_automaton::cell::Cell;
-use cellular_automaton::diagonal::Fast30;
+use cellular_automaton::fast_30::fast30::Fast30;
 use cellular_automaton::pattern::Pattern;
+use cellular_automaton::retention::Retention;
 use cellular_automaton::row::Row;
 use cellular_automaton::rules::{WolframRule};
+use cellular_automaton::simd::Kernel;
 
 pub fn automaton_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("AutomatonEvolve");
@@ -13,6 +16,20 @@ pub fn automaton_benchmark(c: &mut Criterion) {
     // &[100, 1_000, 2_000, 3_000, 4_000, 5_000, 10_000, 20_000, 30_000, 40_000, 50_000]
     for &steps in &[100, 1_000, 2_000, 3_000, 5_000, 10_000, 20_000, 30_000, 40_000, 50_000] {
         group.bench_with_input(BenchmarkId::new("Naive", steps), &steps, |b, &s| {
+            b.iter(|| {
+                let mut config = vec![Cell::new(0); s * 2 + 1];
+                config[s] = Cell::new(1);
+
+                let rule = WolframRule::new(30);
+                let mut row = Row::new(config);
+                for _ in 0..black_box(s) {
+                    row = row.next(&rule);
+                }
+                row
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("Packed", steps), &steps, |b, &s| {
             b.iter(|| {
                 let mut config = vec![Cell::new(0); s * 2 + 1];
                 config[s] = Cell::new(1);
@@ -24,6 +41,22 @@ pub fn automaton_benchmark(c: &mut Criterion) {
             });
         });
 
+        for kernel in [Kernel::Scalar, Kernel::Sse2, Kernel::Avx2] {
+            if !kernel.is_available() {
+                continue;
+            }
+            group.bench_with_input(BenchmarkId::new(format!("{:?}", kernel), steps), &steps, |b, &s| {
+                b.iter(|| {
+                    let mut row = BitRow::new(s * 2 + 1);
+                    row.set(s, 1);
+                    for _ in 0..black_box(s) {
+                        row = row.next_with(kernel, 30, 0, 0);
+                    }
+                    row
+                });
+            });
+        }
+
         group.bench_with_input(BenchmarkId::new("Diagonal", steps), &steps, |b, &s| {
             b.iter(|| {
                 let mut fast = Fast30::new();
@@ -35,6 +68,40 @@ pub fn automaton_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
+pub fn packed_retention(c: &mut Criterion) {
+    let mut group = c.benchmark_group("PackedRetention");
+    group.sample_size(10);
+    for &steps in &[1_000, 5_000, 10_000] {
+        let automaton = |s: usize| {
+            let mut config = vec![Cell::new(0); s * 2 + 1];
+            config[s] = Cell::new(1);
+            let mut automaton = Automaton::new(Row::new(config), Box::new(WolframRule::new(30)));
+            automaton.set_retention(Retention::Nothing);
+            automaton
+        };
+
+        // Chaque appel à `next` décompacte la nouvelle ligne courante.
+        group.bench_with_input(BenchmarkId::new("Next", steps), &steps, |b, &s| {
+            b.iter(|| {
+                let mut automaton = automaton(s);
+                for _ in 0..black_box(s) {
+                    automaton.next();
+                }
+            });
+        });
+
+        // `evolve` ne décompacte que la dernière ligne.
+        group.bench_with_input(BenchmarkId::new("Evolve", steps), &steps, |b, &s| {
+            b.iter(|| {
+                let mut automaton = automaton(s);
+                automaton.evolve(black_box(s as u64));
+            });
+        });
+    }
+
+    group.finish();
+}
+
 pub fn automaton_pattern(c: &mut Criterion) {
     let mut group = c.benchmark_group("Pattern");
     group.sample_size(20);
@@ -88,13 +155,11 @@ pub fn bench_chelou(c: &mut Criterion) {
 pub fn fast30(c: &mut Criterion) {
     let mut group = c.benchmark_group("Fast30");
     group.sample_size(10);
-    let elude = 1;
     let steps = 1_000_000;
-    let id = BenchmarkId::new(format!("Base_s{}", steps), elude);
+    let id = BenchmarkId::new("Base", steps);
     group.bench_with_input(id, &steps, |b, &s| {
         b.iter(|| {
             let mut fast = Fast30::new();
-            fast.set_steps_elude(black_box(elude));
             fast.evolve(black_box(s));
         });
     });
@@ -102,5 +167,5 @@ pub fn fast30(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, fast30);
+criterion_group!(benches, fast30, automaton_benchmark, packed_retention);
 criterion_main!(benches);
@@ -1,41 +1,566 @@
 use std::cmp::max;
+use crate::bit_row::BitRow;
+use crate::boundary::{Boundary, Side};
+use crate::cell::Cell;
+use crate::compiled::{CompiledRule, MultiStepTable};
+use crate::orbit::{pre_period, Brent, Outcome};
+use crate::parallel::evolve_blocks;
+use crate::retention::Retention;
 use crate::row::Row;
-use crate::rules::Rule;
+use crate::rules::{elementary_number, Rule};
+use crate::update::{Scheduler, UpdateScheme};
 
 pub struct Automaton {
     grid: Vec<Row>,
     iteration: usize,
     rule: Box<dyn Rule>,
     col: usize,
+    boundary: Boundary,
+    packed: Option<Packed>,
+    /// Table de la règle, compilée à la construction si la règle est déterministe.
+    compiled: Option<CompiledRule>,
+    /// Table du dernier saut demandé à [`Automaton::jump`].
+    multi_step: Option<MultiStepTable>,
+
+    /// Colonne (signée) de la première cellule de la première ligne ; la colonne 0 est la graine.
+    left_edge: i64,
+    /// État du fond quiescent en mode cône de lumière, `None` pour une grille de largeur fixe.
+    background: Option<u8>,
+    /// Avant-dernière ligne en mode du second ordre, `None` pour une évolution du premier ordre.
+    previous: Option<Row>,
+    /// Ordonnanceur des mises à jour asynchrones, `None` pour une mise à jour synchrone.
+    scheduler: Option<Scheduler>,
+    /// Lignes conservées dans `grid` ; la dernière ligne de `grid` est toujours la ligne courante.
+    retention: Retention,
+    /// Nombre de fils d'exécution utilisés par [`Automaton::evolve`].
+    threads: usize,
+    /// Nombre de générations calculées par chaque fil entre deux synchronisations.
+    time_block: usize,
+}
+
+/// État compacté utilisé quand la règle est élémentaire et la ligne binaire.
+struct Packed {
+    row: BitRow,
+    rule_number: u8,
 }
 
 impl Automaton {
+    /// Crée un automate à partir de sa première ligne et de sa règle.
+    ///
+    /// Si la règle est élémentaire (binaire, de rayon 1) et que la ligne ne contient
+    /// que des états 0 et 1, l'évolution se fait sur une [`BitRow`] compactée,
+    /// de manière transparente pour l'appelant. Sinon, une règle déterministe est compilée
+    /// une fois pour toutes en une [`CompiledRule`], qui évite d'appeler la règle pour chaque cellule.
     pub fn new(first_row: Row, rule: Box<dyn Rule>) -> Self {
         let col = first_row.len();
+        let mut first_row = first_row;
+        first_row.set_iteration(0);
+        let packed = elementary_number(rule.as_ref())
+            .and_then(|rule_number| {
+                BitRow::from_row(&first_row).map(|row| Packed { row, rule_number })
+            });
+
+        let compiled = CompiledRule::compile(rule.as_ref());
+
         Self {
             grid: vec![first_row],
             iteration: 1,
             rule,
-            col
+            col,
+            boundary: Boundary::default(),
+            packed,
+            compiled,
+            multi_step: None,
+            left_edge: -(col.saturating_sub(1) as i64 / 2),
+            background: None,
+            previous: None,
+            scheduler: None,
+            retention: Retention::default(),
+            threads: 1,
+            time_block: 64,
+        }
+    }
+
+    /// Crée un automate du second ordre (construction de Fredkin) à partir de deux lignes consécutives.
+    ///
+    /// La ligne suivante vaut `f(courante) - précédente` modulo le nombre d'états de la règle,
+    /// soit `f(courante) XOR précédente` pour une règle binaire (la règle « 30R » pour la règle 30).
+    /// Une telle évolution est réversible : en donnant les deux lignes dans l'ordre inverse,
+    /// l'automate remonte le temps (voir [`Automaton::into_reversed`]).
+    ///
+    /// # Exemple
+    /// ```text
+    /// let automaton = Automaton::new_second_order(previous, current, Box::new(WolframRule::new(30)));
+    /// ```
+    pub fn new_second_order(previous: Row, current: Row, rule: Box<dyn Rule>) -> Self {
+        assert_eq!(previous.len(), current.len(), "Automaton: both rows should have the same length");
+        let mut automaton = Self::new(previous.clone(), rule);
+        let mut current = current;
+        current.set_iteration(previous.iteration() + 1);
+
+        automaton.grid.push(current);
+        automaton.iteration += 1;
+        automaton.packed = None;
+        automaton.previous = Some(previous);
+        automaton
+    }
+
+    /// Retourne l'automate du second ordre qui remonte le temps à partir des deux dernières lignes.
+    ///
+    /// Après `n` itérations de l'automate retourné, la dernière ligne est celle qui précédait
+    /// de `n` générations l'avant-dernière ligne de `self`. Une condition aux bords qui dépend
+    /// du temps ([`Boundary::Sequence`]) n'est pas renversée.
+    ///
+    /// # Panics
+    /// Si l'automate n'est pas du second ordre.
+    pub fn into_reversed(self) -> Automaton {
+        assert!(self.is_second_order(), "Automaton::into_reversed: only second-order automata can be reversed");
+        let mut grid = self.grid;
+        let current = grid.pop().unwrap();
+        let previous = self.previous.unwrap();
+
+        let mut reversed = Self::new_second_order(current, previous, self.rule);
+        reversed.boundary = self.boundary;
+        reversed.set_retention(self.retention);
+        reversed
+    }
+
+    /// Retourne `true` si l'automate évolue au second ordre.
+    pub fn is_second_order(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    /// Crée un automate dont la ligne s'étend des deux côtés au rythme du cône de lumière.
+    ///
+    /// `origin` est l'indice de la graine dans `first_row` : elle devient la colonne 0.
+    /// À chaque itération, la ligne s'étend d'autant de cellules que la portée de la règle.
+    /// Les cellules hors de la fenêtre stockée forment un fond quiescent, initialement à 0,
+    /// qui évolue lui-même selon la règle (`f(0, 0, 0)`, puis `f(b, b, b)`, ...).
+    /// La condition aux bords n'a alors aucun effet.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let automaton = Automaton::new_light_cone(Row::new(vec![Cell::new(1)]), 0, Box::new(WolframRule::new(30)));
+    /// ```
+    pub fn new_light_cone(first_row: Row, origin: usize, rule: Box<dyn Rule>) -> Self {
+        let mut automaton = Self::new(first_row, rule);
+        automaton.left_edge = -(origin as i64);
+        automaton.background = Some(0);
+        automaton
+    }
+
+    /// Définit la condition aux bords utilisée pour les prochaines itérations.
+    ///
+    /// Par défaut, la ligne est un anneau ([`Boundary::Periodic`]).
+    pub fn set_boundary(&mut self, boundary: Boundary) -> &mut Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Définit l'ordre de mise à jour des cellules pour les prochaines itérations.
+    ///
+    /// Par défaut, toutes les cellules sont mises à jour en même temps. Les schémas aléatoires
+    /// sont reproductibles : ils tirent leurs choix d'un générateur initialisé par leur graine.
+    ///
+    /// # Panics
+    /// Si l'automate est en mode cône de lumière ou du second ordre : les schémas
+    /// asynchrones portent sur une ligne de largeur fixe, au premier ordre.
+    ///
+    /// # Exemple
+    /// ```text
+    /// automaton.set_update_scheme(UpdateScheme::Alpha { alpha: 0.5, seed: 42 });
+    /// ```
+    pub fn set_update_scheme(&mut self, scheme: UpdateScheme) -> &mut Self {
+        let scheduler = Scheduler::new(scheme);
+        assert!(
+            scheduler.is_none() || !(self.is_light_cone() || self.is_second_order()),
+            "Automaton: asynchronous updates need a fixed-width first-order automaton"
+        );
+        if scheduler.is_some() {
+            // La ligne compactée ne suivrait plus les lignes calculées par l'ordonnanceur.
+            self.packed = None;
         }
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Définit les lignes conservées dans [`Automaton::grid`].
+    ///
+    /// Par défaut, toutes les lignes sont conservées ; pour une longue évolution dont seule la fin
+    /// ou un échantillon importe, une autre politique borne la mémoire utilisée. Les lignes déjà
+    /// calculées qui ne relèvent pas de la nouvelle politique sont oubliées.
+    ///
+    /// # Exemple
+    /// ```text
+    /// automaton.set_retention(Retention::Last(100)).evolve(1_000_000);
+    /// ```
+    pub fn set_retention(&mut self, retention: Retention) -> &mut Self {
+        retention.validate();
+        let current = self.grid.pop().unwrap();
+        self.grid.retain(|row| retention.keeps(row.iteration()));
+        self.grid.push(current);
+        if let Retention::Last(n) = retention {
+            let excess = self.grid.len().saturating_sub(n);
+            self.grid.drain(..excess);
+        }
+
+        self.retention = retention;
+        self
+    }
+
+    /// Retourne la politique de conservation des lignes.
+    pub fn retention(&self) -> Retention {
+        self.retention
+    }
+
+    /// Retourne la condition aux bords de l'automate.
+    pub fn boundary(&self) -> &Boundary {
+        &self.boundary
     }
 
     pub fn next(&mut self) {
-        let last_line = self.grid.last().unwrap();
-        let new_line = last_line.next(self.rule.as_ref());
-        self.grid.push(new_line);
-        self.iteration += 1;
+        if self.is_packed() {
+            self.evolve_packed(1);
+            return;
+        }
+
+        let time = self.iteration - 1;
+        let mut new_line = match self.background {
+            _ if self.scheduler.is_some() => {
+                let last_line = self.grid.last().unwrap();
+                let scheduler = self.scheduler.as_mut().unwrap();
+                scheduler.next_row(last_line, self.rule.as_ref(), &self.boundary)
+            }
+            Some(background) => {
+                // Une cellule peut quitter le fond si sa fenêtre touche la ligne stockée.
+                let (left, right) = (self.rule.right_reach(), self.rule.left_reach());
+                let extended = self.grid.last().unwrap().extended(left, right, background);
+                step(&self.compiled, self.rule.as_ref(), &extended, &Boundary::Fixed(background))
+            }
+            None => {
+                let last_line = self.grid.last().unwrap();
+                step(&self.compiled, self.rule.as_ref(), last_line, &self.boundary)
+            }
+        };
+        new_line.set_iteration(time + 1);
+
+        if let Some(previous) = self.previous.take() {
+            let k = self.rule.colours() as u16;
+            let states = (0..new_line.len()).map(|i| {
+                let image = new_line.get(i).unwrap().state() as u16;
+                let before = previous.get(i).unwrap().state() as u16;
+                Cell::new(((image + k - before) % k) as u8)
+            });
+            new_line = Row::new(states.collect());
+            new_line.set_iteration(time + 1);
+            self.previous = self.grid.last().cloned();
+        }
+
+        if let Some(background) = self.background {
+            self.background = Some(self.rule.apply(&vec![background; self.rule.window()]));
+        }
+        self.push_generation(new_line);
+    }
+
+    /// Fait avancer la ligne compactée d'une génération depuis la génération `time`,
+    /// sans la décompacter.
+    fn step_packed(&mut self, time: usize) {
+        let packed = self.packed.as_mut().unwrap();
+        packed.row = match self.background {
+            Some(background) => {
+                let extended = packed.row.extended(1, 1, background);
+                extended.next(packed.rule_number, background, background)
+            }
+            None => {
+                let len = packed.row.len();
+                let cell = |i: usize| packed.row.get(i).unwrap();
+                let left = self.boundary.ghost(Side::Left, 1, len, time, cell);
+                let right = self.boundary.ghost(Side::Right, 1, len, time, cell);
+                packed.row.next(packed.rule_number, left, right)
+            }
+        };
+
+        if let Some(background) = self.background {
+            self.background = Some(self.rule.apply(&vec![background; self.rule.window()]));
+        }
+    }
+
+    /// Ajoute la ligne d'une génération ultérieure, qui devient la ligne courante, en oubliant
+    /// la ligne courante si la politique de conservation ne la retient pas.
+    fn push_generation(&mut self, row: Row) {
+        if !self.retention.keeps(self.iteration - 1) {
+            self.grid.pop();
+        }
+        self.iteration = row.iteration() + 1;
+        self.grid.push(row);
+        self.trim();
+    }
+
+    /// Retire les lignes sorties de la fenêtre d'une politique [`Retention::Last`].
+    fn trim(&mut self) {
+        if let Retention::Last(n) = self.retention {
+            // Les lignes sont retirées par paquets, pour un coût amorti constant.
+            if self.grid.len() >= 2 * n {
+                self.grid.drain(..self.grid.len() - n);
+            }
+        }
+    }
+
+    /// Fait sauter `steps` générations d'un coup à une règle élémentaire sur un anneau,
+    /// grâce à une [`MultiStepTable`] construite au premier saut de cette longueur.
+    ///
+    /// Seule la ligne d'arrivée est calculée : les générations intermédiaires ne sont pas
+    /// conservées, quelle que soit la politique de conservation.
+    ///
+    /// # Panics
+    /// Si la règle n'est pas élémentaire, si la condition aux bords n'est pas périodique, ou si
+    /// l'automate est en mode cône de lumière, du second ordre ou asynchrone.
+    ///
+    /// # Exemple
+    /// ```text
+    /// for _ in 0..1000 {
+    ///     automaton.jump(8); // 8 000 générations
+    /// }
+    /// ```
+    pub fn jump(&mut self, steps: usize) {
+        let rule_number = self.compiled.as_ref().and_then(CompiledRule::elementary_number);
+        let rule_number = rule_number.expect("Automaton::jump: the rule should be elementary");
+        assert!(
+            self.boundary == Boundary::Periodic && !self.is_light_cone() && !self.is_second_order() && self.scheduler.is_none(),
+            "Automaton::jump: only synchronous first-order evolution on a ring can jump"
+        );
+
+        if self.multi_step.as_ref().is_none_or(|table| table.steps() != steps) {
+            self.multi_step = Some(MultiStepTable::new(rule_number, steps));
+        }
+        let next = self.multi_step.as_ref().unwrap().next_row(self.current());
+        if let Some(packed) = &mut self.packed {
+            packed.row = BitRow::from_row(&next).unwrap();
+        }
+
+        self.push_generation(next);
     }
 
+    /// Fait évoluer l'automate de `steps` générations.
+    ///
+    /// Avec plusieurs fils d'exécution (voir [`Automaton::set_threads`]), une ligne compactée
+    /// de largeur fixe évolue en parallèle ; le résultat est identique à l'évolution séquentielle.
+    /// Une ligne compactée n'est décompactée que pour les générations que la politique
+    /// de conservation retient, et pour la dernière.
     pub fn evolve(&mut self, steps: u64)
     {
+        if self.threads > 1 && self.is_packed() && !self.is_light_cone() && !self.is_second_order() && self.scheduler.is_none() {
+            self.evolve_parallel(steps as usize);
+            return;
+        }
+        if self.is_packed() {
+            self.evolve_packed(steps as usize);
+            return;
+        }
+
         for _ in 0..steps {
             self.next();
         }
     }
 
-    pub fn grid(&self) -> &Vec<Row> {
-        &self.grid
+    /// Fait évoluer l'automate jusqu'à ce que la ligne courante vérifie `predicate`, que son orbite
+    /// entre dans un cycle, ou au plus `max_steps` itérations.
+    ///
+    /// Le prédicat reçoit la génération et la ligne, à commencer par la ligne courante. Les cycles
+    /// sont détectés par l'algorithme de Brent, qui ne conserve qu'une ligne en plus de la ligne
+    /// de départ : la mémoire reste bornée, quelle que soit la politique de conservation.
+    /// La pré-période et la période sont comptées depuis la ligne courante au moment de l'appel.
+    ///
+    /// Seule une évolution qui revient forcément sur ses pas est surveillée : règle déterministe,
+    /// largeur fixe, premier ordre, mise à jour synchrone et condition aux bords indépendante du temps.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let outcome = automaton.evolve_until(10_000, |_, row| (0..row.len()).all(|i| row.get(i).unwrap().state() == 0));
+    /// if let Outcome::Cycle { pre_period, period, .. } = outcome { ... }
+    /// ```
+    pub fn evolve_until(&mut self, max_steps: u64, mut predicate: impl FnMut(usize, &Row) -> bool) -> Outcome {
+        let start = self.current().clone();
+        let mut brent = self.is_finite_deterministic().then(|| Brent::new(&start));
+        if predicate(self.iteration - 1, self.current()) {
+            return Outcome::Matched { steps: 0 };
+        }
+
+        for steps in 1..=max_steps {
+            self.next();
+            if predicate(self.iteration - 1, self.current()) {
+                return Outcome::Matched { steps };
+            }
+            if let Some(period) = brent.as_mut().and_then(|brent| brent.observe(self.current())) {
+                let pre_period = pre_period(&start, period, |row| step(&self.compiled, self.rule.as_ref(), row, &self.boundary));
+                return Outcome::Cycle { steps, pre_period, period };
+            }
+        }
+        Outcome::Exhausted { steps: max_steps }
+    }
+
+    /// Retourne `true` si la ligne suivante ne dépend que de la ligne courante, parmi un nombre
+    /// fini de lignes possibles.
+    fn is_finite_deterministic(&self) -> bool {
+        !self.is_light_cone()
+            && !self.is_second_order()
+            && self.scheduler.is_none()
+            && self.rule.is_deterministic()
+            && !matches!(self.boundary, Boundary::Sequence { .. })
+    }
+
+    /// Définit le nombre de fils d'exécution entre lesquels la ligne est répartie par
+    /// [`Automaton::evolve`].
+    ///
+    /// Seule une ligne compactée (règle élémentaire, états binaires) de largeur fixe, évoluant
+    /// au premier ordre et de manière synchrone, est répartie ; sinon l'évolution reste séquentielle.
+    /// La répartition n'est rentable que pour de très longues lignes (au moins un million de cellules).
+    /// Les fils sont créés une fois par appel à [`Automaton::evolve`] et servent à tous ses blocs.
+    ///
+    /// # Exemple
+    /// ```text
+    /// automaton.set_threads(8).set_time_block(256).set_retention(Retention::Nothing);
+    /// automaton.evolve(100_000);
+    /// ```
+    pub fn set_threads(&mut self, threads: usize) -> &mut Self {
+        assert!(threads > 0, "Automaton: at least one thread is needed");
+        self.threads = threads;
+        self
+    }
+
+    /// Définit le nombre de générations (64 par défaut) que chaque fil calcule sur son morceau
+    /// de ligne avant de se synchroniser avec les autres.
+    ///
+    /// Un bloc plus long espace les synchronisations, mais chaque morceau est prolongé d'autant
+    /// de cellules de chaque côté, calculées en double par les fils voisins.
+    pub fn set_time_block(&mut self, generations: usize) -> &mut Self {
+        assert!(generations > 0, "Automaton: a time block contains at least one generation");
+        self.time_block = generations;
+        self
+    }
+
+    /// Retourne le filtre des générations, jusqu'à la génération `last`, dont la ligne doit être
+    /// décompactée : celles que la politique de conservation retient, et la dernière.
+    fn wanted(&self, last: usize) -> impl Fn(usize) -> bool + Sync + use<> {
+        let retention = self.retention;
+        move |generation: usize| match retention {
+            Retention::All => true,
+            Retention::Last(n) => generation + n > last,
+            _ => retention.keeps(generation) || generation == last,
+        }
+    }
+
+    /// Évolution séquentielle d'une ligne compactée. Seules les lignes que la politique
+    /// de conservation retient, et la dernière, sont décompactées.
+    fn evolve_packed(&mut self, steps: usize) {
+        let first = self.iteration;
+        let wanted = self.wanted(first - 1 + steps);
+        for generation in first..first + steps {
+            self.step_packed(generation - 1);
+            if wanted(generation) {
+                let mut row = self.packed.as_ref().unwrap().row.to_row();
+                row.set_iteration(generation);
+                self.push_generation(row);
+            }
+        }
+    }
+
+    /// Évolution parallèle, par blocs de `time_block` générations, sur des fils créés une fois
+    /// pour tout l'appel. Seules les lignes que la politique de conservation retient sont décompactées.
+    fn evolve_parallel(&mut self, steps: usize) {
+        let wanted = self.wanted(self.iteration - 1 + steps);
+
+        let packed = self.packed.as_ref().unwrap();
+        let (row, rule_number, boundary) = (packed.row.clone(), packed.rule_number, self.boundary.clone());
+        let (time, time_block, threads) = (self.iteration - 1, self.time_block, self.threads);
+        let last_row = evolve_blocks(&row, rule_number, &boundary, time, steps, time_block, threads, wanted, |generation, row| {
+            let mut row = row.to_row();
+            row.set_iteration(generation);
+            self.push_generation(row);
+        });
+        self.packed.as_mut().unwrap().row = last_row;
+    }
+
+    /// Retourne `true` si l'automate évolue sur une ligne compactée.
+    pub fn is_packed(&self) -> bool {
+        self.packed.is_some()
+    }
+
+    /// Retourne `true` si la ligne grandit avec le cône de lumière.
+    pub fn is_light_cone(&self) -> bool {
+        self.background.is_some()
+    }
+
+    /// Retourne la colonne (signée) de la première cellule stockée de la ligne `generation`.
+    ///
+    /// La colonne 0 est celle de la graine : le milieu de la première ligne pour une grille
+    /// de largeur fixe, ou l'origine donnée à [`Automaton::new_light_cone`].
+    pub fn left_edge(&self, generation: usize) -> i64 {
+        if self.is_light_cone() {
+            self.left_edge - (self.rule.right_reach() * generation) as i64
+        } else {
+            self.left_edge
+        }
+    }
+
+    /// Retourne la ligne de la génération donnée (0 pour la première ligne),
+    /// ou `None` si elle n'a pas été conservée.
+    pub fn row(&self, generation: usize) -> Option<&Row> {
+        let grid = self.grid();
+        let index = grid.binary_search_by_key(&generation, Row::iteration).ok()?;
+        grid.get(index)
+    }
+
+    /// Retourne la ligne courante, la dernière calculée.
+    pub fn current(&self) -> &Row {
+        self.grid.last().unwrap()
+    }
+
+    /// Retourne la génération de la plus ancienne ligne conservée.
+    pub fn first_generation(&self) -> usize {
+        self.grid()[0].iteration()
+    }
+
+    /// Retourne la cellule de la ligne `generation` à la colonne (signée) `column`,
+    /// ou `None` si elle n'est pas stockée.
+    pub fn cell(&self, generation: usize, column: i64) -> Option<&Cell> {
+        let index = column - self.left_edge(generation);
+        if index < 0 {
+            return None;
+        }
+        self.row(generation)?.get(index as usize)
+    }
+
+    /// Retourne l'état de la ligne `generation` à la colonne `column`.
+    ///
+    /// En mode cône de lumière, les cellules hors de la fenêtre stockée prennent
+    /// l'état du fond quiescent à cette génération (si la règle est déterministe).
+    pub fn state(&self, generation: usize, column: i64) -> Option<u8> {
+        if let Some(cell) = self.cell(generation, column) {
+            return Some(cell.state());
+        }
+        if generation >= self.iteration || !self.is_light_cone() || !self.rule.is_deterministic() {
+            return None;
+        }
+
+        let mut background = 0;
+        for _ in 0..generation {
+            background = self.rule.apply(&vec![background; self.rule.window()]);
+        }
+        Some(background)
+    }
+
+    /// Retourne le nombre de cellules de la dernière ligne.
+    pub fn width(&self) -> usize {
+        self.grid.last().map_or(0, Row::len)
+    }
+
+    /// Retourne les lignes conservées, de la plus ancienne à la ligne courante.
+    pub fn grid(&self) -> &[Row] {
+        match self.retention {
+            Retention::Last(n) => &self.grid[self.grid.len().saturating_sub(n)..],
+            _ => &self.grid,
+        }
     }
 
     pub fn iteration(&self) -> usize {
@@ -51,6 +576,254 @@ impl Automaton {
     }
 
     pub fn to_string(&self) -> String {
-        self.grid.iter().map(|r| r.to_string() + "\n").collect()
+        self.grid().iter().map(|r| r.to_string() + "\n").collect()
+    }
+}
+
+/// Calcule la ligne suivante avec la règle compilée si elle existe, avec la règle sinon.
+fn step(compiled: &Option<CompiledRule>, rule: &dyn Rule, row: &Row, boundary: &Boundary) -> Row {
+    match compiled {
+        Some(compiled) => compiled.next_row(row, boundary),
+        None => row.next_with_boundary(rule, boundary),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Rule30, WolframRule};
+
+    #[test]
+    fn test_packed_matches_row_next() {
+        let mut config = vec![Cell::new(0); 101];
+        config[50] = Cell::new(1);
+        config[0] = Cell::new(1);
+        let first_row = Row::new(config);
+
+        let mut automaton = Automaton::new(first_row.clone(), Box::new(WolframRule::new(110)));
+        assert!(automaton.is_packed());
+        automaton.evolve(80);
+
+        let mut row = first_row;
+        for expected in automaton.grid().iter().skip(1) {
+            row = row.next(&WolframRule::new(110));
+            assert_eq!(expected.to_string(), row.to_string());
+        }
+
+        let automaton = Automaton::new(Row::new(vec![Cell::new(2)]), Box::new(Rule30));
+        assert!(!automaton.is_packed());
+    }
+
+    #[test]
+    fn test_light_cone_matches_wide_ring() {
+        for rule_number in [30, 1, 110, 57] {
+            let steps = 40;
+            let mut config = vec![Cell::new(0); 2 * steps + 1];
+            config[steps] = Cell::new(1);
+            let mut wide = Automaton::new(Row::new(config), Box::new(WolframRule::new(rule_number)));
+            wide.evolve(steps as u64);
+
+            let mut cone = Automaton::new_light_cone(Row::new(vec![Cell::new(1)]), 0, Box::new(WolframRule::new(rule_number)));
+            cone.evolve(steps as u64);
+
+            for generation in 0..=steps {
+                assert_eq!(cone.grid()[generation].len(), 2 * generation + 1);
+                assert_eq!(cone.left_edge(generation), -(generation as i64));
+                for column in -(steps as i64)..=steps as i64 {
+                    assert_eq!(cone.state(generation, column), wide.state(generation, column), "rule {} at ({}, {})", rule_number, generation, column);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_light_cone_unpacked_matches_packed() {
+        let seed = Row::new(vec![Cell::new(1), Cell::new(0), Cell::new(1)]);
+        let mut packed = Automaton::new_light_cone(seed.clone(), 1, Box::new(WolframRule::new(45)));
+        let mut unpacked = Automaton::new_light_cone(seed.extended(0, 0, 0), 1, Box::new(WolframRule::new(45)));
+        unpacked.packed = None;
+
+        packed.evolve(30);
+        unpacked.evolve(30);
+        assert_eq!(packed.to_string(), unpacked.to_string());
+    }
+
+    #[test]
+    fn test_light_cone_larger_windows() {
+        for (rule_number, window) in [(6u128, 2usize), (0x6996_9669, 5), (0x0F0F_3C3C, 5), (0x5A3C, 4)] {
+            let steps = 12;
+            let mut config = vec![Cell::new(0); 4 * steps + 3];
+            config[2 * steps + 1] = Cell::new(1);
+            config[2 * steps + 2] = Cell::new(1);
+            let mut wide = Automaton::new(Row::new(config), Box::new(WolframRule::with_window(rule_number, window)));
+            wide.evolve(steps as u64);
+
+            let seed = Row::new(vec![Cell::new(1), Cell::new(1)]);
+            let mut cone = Automaton::new_light_cone(seed, 0, Box::new(WolframRule::with_window(rule_number, window)));
+            cone.evolve(steps as u64);
+
+            let rule = WolframRule::with_window(rule_number, window);
+            for generation in 0..=steps {
+                let growth = rule.left_reach() + rule.right_reach();
+                assert_eq!(cone.grid()[generation].len(), 2 + growth * generation);
+                for column in -(2 * steps as i64)..=(2 * steps as i64) {
+                    assert_eq!(cone.state(generation, column), wide.state(generation, column), "window {} at ({}, {})", window, generation, column);
+                }
+            }
+        }
+    }
+
+    fn random_row(len: usize, seed: u64, colours: u8) -> Row {
+        let mut x = seed;
+        let states = (0..len).map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            Cell::new((x % colours as u64) as u8)
+        });
+        Row::new(states.collect())
+    }
+
+    #[test]
+    fn test_second_order_step() {
+        let previous = Row::new([0, 1, 1, 0, 0].iter().map(|&s| Cell::new(s)).collect());
+        let current = Row::new([0, 0, 1, 0, 0].iter().map(|&s| Cell::new(s)).collect());
+        let mut automaton = Automaton::new_second_order(previous, current, Box::new(WolframRule::new(30)));
+        automaton.set_boundary(Boundary::Fixed(0));
+        automaton.next();
+
+        // f(..#..) = .###. puis XOR .##.. = ...#.
+        assert_eq!(automaton.grid().last().unwrap().to_string(), "...#.");
+        assert_eq!(automaton.iteration(), 3);
+    }
+
+    #[test]
+    fn test_second_order_runs_backwards() {
+        let steps = 64;
+        for (rule, colours, boundary) in [
+            (Box::new(WolframRule::new(30)) as Box<dyn Rule>, 2, Boundary::Periodic),
+            (Box::new(WolframRule::new(110)), 2, Boundary::Reflecting),
+            (Box::new(WolframRule::with_colours(&"123456789012".parse().unwrap(), 3, 3)), 3, Boundary::Fixed(2)),
+        ] {
+            let seed = random_row(57, 0xDEAD_BEEF, colours);
+            let second = random_row(57, 0x1234_5678, colours);
+            let mut automaton = Automaton::new_second_order(seed.clone(), second.clone(), rule);
+            automaton.set_boundary(boundary);
+            automaton.evolve(steps);
+
+            let forward: Vec<String> = automaton.grid().iter().map(Row::to_string).collect();
+            let mut backward = automaton.into_reversed();
+            backward.evolve(steps);
+
+            let mut replay: Vec<String> = backward.grid().iter().map(Row::to_string).collect();
+            replay.reverse();
+            assert_eq!(replay, forward);
+            assert_eq!(backward.grid().last().unwrap().to_string(), seed.to_string());
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_retention_policies() {
+        let mut config = vec![Cell::new(0); 61];
+        config[30] = Cell::new(1);
+        let mut reference = Automaton::new(Row::new(config.clone()), Box::new(WolframRule::new(30)));
+        reference.evolve(50);
+
+        for retention in [Retention::All, Retention::Last(7), Retention::Every(8), Retention::Nothing] {
+            let mut automaton = Automaton::new(Row::new(config.clone()), Box::new(WolframRule::new(30)));
+            automaton.set_retention(retention).evolve(20);
+            automaton.next();
+            automaton.evolve(29);
+            assert_eq!(automaton.iteration(), reference.iteration());
+            assert_eq!(automaton.current().to_string(), reference.current().to_string());
+
+            let kept: Vec<usize> = automaton.grid().iter().map(Row::iteration).collect();
+            let expected: Vec<usize> = match retention {
+                Retention::All => (0..=50).collect(),
+                Retention::Last(n) => (51 - n..=50).collect(),
+                Retention::Every(k) => (0..50).step_by(k).chain([50]).collect(),
+                Retention::Nothing => vec![50],
+            };
+            assert_eq!(kept, expected, "{:?}", retention);
+            if let Retention::Last(n) = retention {
+                assert!(automaton.grid.len() < 2 * n, "at most 2n - 1 rows are stored");
+            }
+
+            for generation in 0..=50 {
+                let row = automaton.row(generation).map(Row::to_string);
+                let expected = expected.contains(&generation).then(|| reference.grid()[generation].to_string());
+                assert_eq!(row, expected, "{:?} at {}", retention, generation);
+                assert_eq!(automaton.cell(generation, 3).is_some(), expected.is_some());
+            }
+        }
+
+        let mut second_order = Automaton::new_second_order(random_row(40, 1, 2), random_row(40, 2, 2), Box::new(WolframRule::new(30)));
+        second_order.set_retention(Retention::Nothing).evolve(20);
+        assert_eq!(second_order.grid().len(), 1);
+        let mut backward = second_order.into_reversed();
+        backward.evolve(20);
+        assert_eq!(backward.current().to_string(), random_row(40, 1, 2).to_string());
+    }
+
+    #[test]
+    fn test_jump() {
+        for rule_number in [30, 110, 45] {
+            let first_row = random_row(83, rule_number as u64, 2);
+            let mut reference = Automaton::new(first_row.clone(), Box::new(WolframRule::new(rule_number)));
+            reference.evolve(60);
+
+            let mut automaton = Automaton::new(first_row, Box::new(WolframRule::new(rule_number)));
+            for _ in 0..10 {
+                automaton.jump(6);
+            }
+            assert_eq!(automaton.iteration(), reference.iteration());
+            assert_eq!(automaton.current().to_string(), reference.current().to_string());
+            assert_eq!(automaton.row(30).unwrap().to_string(), reference.row(30).unwrap().to_string());
+            assert!(automaton.row(31).is_none());
+
+            automaton.next();
+            reference.next();
+            assert_eq!(automaton.current().to_string(), reference.current().to_string());
+        }
+    }
+
+    #[test]
+    fn test_threads_match_sequential() {
+        for (rule_number, boundary) in [(30, Boundary::Periodic), (110, Boundary::Fixed(1)), (90, Boundary::Reflecting)] {
+            for retention in [Retention::All, Retention::Last(3), Retention::Every(5), Retention::Nothing] {
+                let first_row = random_row(64 * 9 + 17, rule_number as u64, 2);
+                let mut sequential = Automaton::new(first_row.clone(), Box::new(WolframRule::new(rule_number)));
+                sequential.set_boundary(boundary.clone()).set_retention(retention).evolve(150);
+
+                let mut parallel = Automaton::new(first_row, Box::new(WolframRule::new(rule_number)));
+                parallel.set_boundary(boundary.clone()).set_retention(retention).set_threads(4).set_time_block(40);
+                parallel.evolve(100);
+                parallel.evolve(50);
+
+                assert_eq!(parallel.iteration(), sequential.iteration());
+                assert_eq!(parallel.to_string(), sequential.to_string(), "rule {} with {:?}", rule_number, retention);
+                parallel.next();
+                sequential.next();
+                assert_eq!(parallel.current().to_string(), sequential.current().to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_threads_with_many_blocks() {
+        // Des largeurs qui ne se répartissent pas également entre les fils, et des évolutions
+        // de plusieurs blocs dont le dernier est incomplet.
+        for (width, threads, time_block) in [(64 * 5 + 3, 3, 7), (1000, 6, 13), (130, 4, 1), (64 * 7, 5, 64)] {
+            let first_row = random_row(width, width as u64, 2);
+            let mut sequential = Automaton::new(first_row.clone(), Box::new(WolframRule::new(30)));
+            sequential.set_boundary(Boundary::Sequence { left: vec![1, 0], right: vec![0, 0, 1] }).evolve(201);
+
+            let mut parallel = Automaton::new(first_row, Box::new(WolframRule::new(30)));
+            parallel.set_boundary(Boundary::Sequence { left: vec![1, 0], right: vec![0, 0, 1] });
+            parallel.set_threads(threads).set_time_block(time_block).evolve(201);
+
+            assert_eq!(parallel.iteration(), sequential.iteration());
+            assert_eq!(parallel.to_string(), sequential.to_string(), "{} cells, {} threads, blocks of {}", width, threads, time_block);
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use std::fmt;
+use crate::cell::Cell;
+use crate::life::Rule2D;
+use crate::palette::{Palette, DEFAULT_SYMBOLS};
+
+/// Voisinage d'une cellule d'une grille bidimensionnelle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Neighbourhood {
+    /// Les 8 cellules qui touchent la cellule par un côté ou un coin.
+    #[default]
+    Moore,
+    /// Les 4 cellules qui touchent la cellule par un côté.
+    VonNeumann,
+}
+
+impl Neighbourhood {
+    /// Retourne les décalages `(dx, dy)` des voisines.
+    pub fn offsets(&self) -> &'static [(i64, i64)] {
+        match self {
+            Neighbourhood::Moore => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+            Neighbourhood::VonNeumann => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+        }
+    }
+}
+
+/// Comportement aux bords d'une grille bidimensionnelle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Edges {
+    /// Les bords opposés sont recollés : la grille est un tore.
+    #[default]
+    Torus,
+    /// Les cellules hors de la grille sont mortes (état 0).
+    Bounded,
+}
+
+/// Automate cellulaire sur une grille bidimensionnelle de largeur et hauteur fixes.
+///
+/// Seule la génération courante est conservée. À chaque itération, seules les cellules
+/// dont le voisinage a changé lors de l'itération précédente sont recalculées :
+/// le coût d'une itération est proportionnel à l'activité, et non à la taille de la grille,
+/// ce qui rend l'évolution de grands motifs clairsemés peu coûteuse.
+pub struct Automaton2D {
+    cells: Vec<Cell>,
+    width: usize,
+    height: usize,
+    iteration: usize,
+    rule: Box<dyn Rule2D>,
+    neighbourhood: Neighbourhood,
+    edges: Edges,
+
+    /// Indices des cellules à recalculer à la prochaine itération.
+    active: Vec<usize>,
+    /// `true` pour les cellules présentes dans `active`.
+    is_active: Vec<bool>,
+}
+
+impl Automaton2D {
+    /// Crée un automate dont toutes les cellules sont dans l'état 0.
+    pub fn new(width: usize, height: usize, rule: Box<dyn Rule2D>) -> Self {
+        Self::from_cells(width, height, vec![Cell::new(0); width * height], rule)
+    }
+
+    /// Crée un automate à partir de ses cellules, ligne par ligne, de haut en bas.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<Cell>, rule: Box<dyn Rule2D>) -> Self {
+        assert_eq!(cells.len(), width * height, "Automaton2D: one cell per position is needed");
+        let mut automaton = Self {
+            cells,
+            width,
+            height,
+            iteration: 1,
+            rule,
+            neighbourhood: Neighbourhood::Moore,
+            edges: Edges::Torus,
+            active: vec![],
+            is_active: vec![false; width * height],
+        };
+        automaton.activate_all();
+        automaton
+    }
+
+    /// Crée un automate à partir de lignes de symboles (`.` pour 0, `#` pour 1, `+` pour 2...).
+    /// Les lignes plus courtes que la plus longue sont complétées par des cellules à 0.
+    ///
+    /// Retourne `None` si un symbole n'appartient pas à la palette par défaut
+    /// ([`DEFAULT_SYMBOLS`]).
+    ///
+    /// # Exemple
+    /// ```text
+    /// let glider = Automaton2D::from_lines(&[".#.", "..#", "###"], Box::new(LifeRule::life())).unwrap();
+    /// ```
+    pub fn from_lines(lines: &[&str], rule: Box<dyn Rule2D>) -> Option<Self> {
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let mut automaton = Self::new(width, lines.len(), rule);
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let state = DEFAULT_SYMBOLS.iter().position(|&symbol| symbol == c)?;
+                automaton.set(x, y, state as u8);
+            }
+        }
+        Some(automaton)
+    }
+
+    /// Change le voisinage utilisé par la règle.
+    pub fn set_neighbourhood(&mut self, neighbourhood: Neighbourhood) -> &mut Self {
+        self.neighbourhood = neighbourhood;
+        self.activate_all();
+        self
+    }
+
+    /// Change le comportement aux bords de la grille.
+    pub fn set_edges(&mut self, edges: Edges) -> &mut Self {
+        self.edges = edges;
+        self.activate_all();
+        self
+    }
+
+    /// Modifie l'état d'une cellule.
+    pub fn set(&mut self, x: usize, y: usize, state: u8) -> &mut Self {
+        assert!(x < self.width && y < self.height, "Automaton2D: cell out of the grid");
+        let index = y * self.width + x;
+        self.cells[index].set_state(state);
+        self.activate_around(index);
+        self
+    }
+
+    /// Retourne la cellule en `(x, y)`, ou `None` hors de la grille.
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Calcule la génération suivante.
+    pub fn next(&mut self) {
+        let mut neighbours = Vec::with_capacity(8);
+        let mut changes = vec![];
+        for &index in &self.active {
+            neighbours.clear();
+            neighbours.extend(self.neighbours(index).map(|j| j.map_or(0, |j| self.cells[j].state())));
+            let state = self.cells[index].state();
+            let next = self.rule.apply(state, &neighbours);
+            if next != state {
+                changes.push((index, next));
+            }
+        }
+
+        for &index in &self.active {
+            self.is_active[index] = false;
+        }
+        self.active.clear();
+
+        for (index, state) in changes {
+            self.cells[index].set_state(state);
+            self.activate_around(index);
+        }
+        self.iteration += 1;
+    }
+
+    pub fn evolve(&mut self, steps: u64) {
+        for _ in 0..steps {
+            self.next();
+        }
+    }
+
+    /// Retourne le nombre de cellules dans un état non nul.
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.state() != 0).count()
+    }
+
+    /// Retourne le nombre de cellules qui seront recalculées à la prochaine itération.
+    pub fn activity(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Retourne la grille rendue selon une palette, une ligne de texte par ligne de la grille.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let rule = GenerationsRule::brians_brain();
+    /// let palette = rule.palette();
+    /// let automaton = Automaton2D::new(80, 40, Box::new(rule));
+    /// println!("{}", automaton.to_string_with(&palette));
+    /// ```
+    pub fn to_string_with(&self, palette: &Palette) -> String {
+        self.cells
+            .chunks(self.width.max(1))
+            .map(|line| line.iter().map(|c| c.display_with(palette)).collect::<String>() + "\n")
+            .collect()
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Retourne les indices des voisines d'une cellule, `None` pour une voisine hors de la grille.
+    fn neighbours(&self, index: usize) -> impl Iterator<Item = Option<usize>> + '_ {
+        let (x, y) = ((index % self.width) as i64, (index / self.width) as i64);
+        let (width, height) = (self.width as i64, self.height as i64);
+        self.neighbourhood.offsets().iter().map(move |&(dx, dy)| {
+            let (nx, ny) = (x + dx, y + dy);
+            match self.edges {
+                Edges::Torus => Some((ny.rem_euclid(height) * width + nx.rem_euclid(width)) as usize),
+                Edges::Bounded if (0..width).contains(&nx) && (0..height).contains(&ny) => Some((ny * width + nx) as usize),
+                Edges::Bounded => None,
+            }
+        })
+    }
+
+    /// Marque une cellule et ses voisines comme à recalculer.
+    fn activate_around(&mut self, index: usize) {
+        let around: Vec<usize> = self.neighbours(index).flatten().chain([index]).collect();
+        for j in around {
+            if !self.is_active[j] {
+                self.is_active[j] = true;
+                self.active.push(j);
+            }
+        }
+    }
+
+    fn activate_all(&mut self) {
+        self.active = (0..self.cells.len()).collect();
+        self.is_active.fill(true);
+    }
+}
+
+impl fmt::Display for Automaton2D {
+    /// Affiche la grille ligne par ligne, avec le symbole de chaque état.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.cells.chunks(self.width.max(1)) {
+            let line: String = line.iter().map(Cell::display).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life::LifeRule;
+    use crate::random::Random;
+
+    #[test]
+    fn test_blinker_and_glider() {
+        let mut blinker = Automaton2D::from_lines(&[".....", "..#..", "..#..", "..#..", "....."], Box::new(LifeRule::life())).unwrap();
+        blinker.set_edges(Edges::Bounded).next();
+        assert_eq!(blinker.to_string(), ".....\n.....\n.###.\n.....\n.....\n");
+        blinker.next();
+        assert_eq!(blinker.to_string(), ".....\n..#..\n..#..\n..#..\n.....\n");
+        assert_eq!(blinker.iteration(), 3);
+        assert!(Automaton2D::from_lines(&["..#", ".?."], Box::new(LifeRule::life())).is_none());
+        assert!(Automaton2D::from_lines(&["#x"], Box::new(LifeRule::life())).is_none());
+
+        let mut glider = Automaton2D::new(8, 8, Box::new(LifeRule::life()));
+        glider.set(1, 0, 1).set(2, 1, 1).set(0, 2, 1).set(1, 2, 1).set(2, 2, 1);
+        let start = glider.to_string();
+        glider.evolve(4);
+        assert_eq!(glider.get(2, 1).unwrap().state(), 1);
+        assert_eq!(glider.get(3, 3).unwrap().state(), 1);
+        glider.evolve(28);
+        assert_eq!(glider.to_string(), start);
+        assert_eq!(glider.population(), 5);
+    }
+
+    #[test]
+    fn test_sparse_pattern_keeps_activity_small() {
+        let mut automaton = Automaton2D::new(1000, 1000, Box::new(LifeRule::life()));
+        automaton.set(500, 500, 1).set(501, 500, 1).set(502, 500, 1);
+        automaton.next();
+        assert!(automaton.activity() < 30);
+        automaton.evolve(99);
+        assert_eq!(automaton.population(), 3);
+        assert!(automaton.activity() < 30);
+    }
+
+    #[test]
+    fn test_matches_full_update() {
+        let mut random = Random::new(17);
+        for (neighbourhood, edges, rule) in [
+            (Neighbourhood::Moore, Edges::Torus, LifeRule::life()),
+            (Neighbourhood::Moore, Edges::Bounded, LifeRule::high_life()),
+            (Neighbourhood::VonNeumann, Edges::Torus, LifeRule::new(&[1, 3], &[0, 2, 4])),
+            (Neighbourhood::VonNeumann, Edges::Bounded, LifeRule::seeds()),
+        ] {
+            let cells: Vec<Cell> = (0..24 * 17).map(|_| Cell::new(random.bernoulli(0.35) as u8)).collect();
+            let mut sparse = Automaton2D::from_cells(24, 17, cells.clone(), Box::new(rule));
+            let mut full = Automaton2D::from_cells(24, 17, cells, Box::new(rule));
+            sparse.set_neighbourhood(neighbourhood).set_edges(edges);
+            full.set_neighbourhood(neighbourhood).set_edges(edges);
+            for _ in 0..40 {
+                sparse.next();
+                full.activate_all();
+                full.next();
+                assert_eq!(sparse.to_string(), full.to_string());
+            }
+        }
+    }
+}
@@ -12,8 +12,8 @@ pub enum DIAGONAL {
 pub struct AutomatonAnalysis<'a> {
     automaton: &'a Automaton,
 
-    diagonals_left: Vec<Vec<&'a Cell>>,
-    diagonals_right: Vec<Vec<&'a Cell>>,
+    diagonals_left: Vec<Sampled<'a>>,
+    diagonals_right: Vec<Sampled<'a>>,
 
     current_period_left: usize,
     current_period_right: usize,
@@ -22,7 +22,22 @@ pub struct AutomatonAnalysis<'a> {
     multiplier_right: usize,
 }
 
+/// Cellules d'une diagonale lues sur les lignes conservées : `cells[j]` est la cellule
+/// `first + j * stride` de la diagonale complète.
+struct Sampled<'a> {
+    cells: Vec<&'a Cell>,
+    first: usize,
+    stride: usize,
+}
+
 impl<'a> AutomatonAnalysis<'a> {
+    /// Prépare l'analyse d'un automate.
+    ///
+    /// Seules les lignes conservées sont analysées : les diagonales commencent à la plus
+    /// ancienne ligne conservée et, avec [`Retention::Every`](crate::retention::Retention::Every)`(k)`,
+    /// ne contiennent que les cellules des lignes échantillonnées. Les périodes et les décalages
+    /// retournés par [`extract_patterns`](Self::extract_patterns) restent comptés en générations.
+    /// Avec [`Retention::Nothing`](crate::retention::Retention::Nothing), les diagonales sont vides.
     pub fn new(automaton: &'a Automaton) -> Self {
         Self {
             automaton,
@@ -63,13 +78,15 @@ impl<'a> AutomatonAnalysis<'a> {
         &self,
         n: usize,
         diagonal: &DIAGONAL,
-    ) -> Option<Vec<&'a Cell>> {
-        let grid = self.automaton.grid();
-        let iteration = self.automaton.iteration();
-        let middle = (self.automaton.col() - 1)/2;
+    ) -> Option<Sampled<'a>> {
+        let automaton = self.automaton;
+        let iteration = automaton.iteration();
+        let horizon = self.horizon();
         let mut result = Vec::new();
+        let mut first = None;
+        let mut stride = 1;
 
-        if n > middle { return None; }
+        if n > horizon { return None; }
 
         let multiplier = match diagonal {
             DIAGONAL::LEFT => { self.multiplier_left },
@@ -79,44 +96,57 @@ impl<'a> AutomatonAnalysis<'a> {
         let mut offset = 0;
 
         for i in (n..iteration).step_by(multiplier) {
-            if n + offset > middle || (middle + offset) > self.automaton.col()
+            if n + offset > horizon
             {
                 break;
             }
 
-            let col = match diagonal {
-                DIAGONAL::LEFT => middle - offset,
-                DIAGONAL::RIGHT => middle + offset
+            let column = match diagonal {
+                DIAGONAL::LEFT => -(offset as i64),
+                DIAGONAL::RIGHT => offset as i64
             };
 
-            if let Some(row) = grid.get(i) {
-                if col < row.len() {
-                    let cell = row.get(col).unwrap();
-                    result.push(cell);
+            if let Some(cell) = automaton.cell(i, column).filter(|_| automaton.retention().keeps(i)) {
+                match first {
+                    None => { first = Some(offset); }
+                    Some(start) if result.len() == 1 => { stride = offset - start; }
+                    Some(_) => {}
                 }
+                result.push(cell);
             }
 
             offset += 1;
         }
 
-        Some(result)
+        Some(Sampled { cells: result, first: first.unwrap_or(0), stride })
+    }
+
+    /// Dernière génération non faussée par les bords de la grille.
+    ///
+    /// Pour une grille de largeur fixe, le cône de lumière de la graine atteint les bords
+    /// à la génération `(col - 1) / 2`. Une ligne en cône de lumière n'a pas de bords.
+    fn horizon(&self) -> usize {
+        if self.automaton.is_light_cone() {
+            usize::MAX
+        } else {
+            (self.automaton.col() - 1) / 2
+        }
     }
 
 
     pub fn rightmost_same_state(&self, cell_type: Cell) -> BTreeMap<u16, u16>
     {
-        let middle = (self.automaton.col() - 1)/2;
         let mut result: BTreeMap<u16, u16> = BTreeMap::new();
-        for i in 0..self.automaton.max_iteration() {
+        for i in self.automaton.first_generation()..self.automaton.iteration() {
+            if self.automaton.row(i).is_none() {
+                continue;
+            }
             let mut counter: u16 = 1;
             loop {
-                let index = middle + i - counter as usize;
-                let current_row = self.automaton.grid().get(i).unwrap();
-                let current_cell = match current_row.get(index) {
-                    None => {
-                        println!("Cell not found: {} at row: {} and counter {}", index, i, counter);
-                        break;
-                    }
+                let column = i as i64 - counter as i64;
+                let current_cell = match self.automaton.cell(i, column) {
+                    // La plage atteint le bord de la ligne stockée.
+                    None => { break; }
                     Some(p) => { p }
                 };
 
@@ -133,6 +163,13 @@ impl<'a> AutomatonAnalysis<'a> {
         result
     }
 
+    /// Cherche un motif périodique sur chaque diagonale extraite, à partir de la cellule
+    /// `offset_f(i)` de la `i`-ième diagonale, et retourne le motif, sa période et la cellule
+    /// où il commence.
+    ///
+    /// Les périodes et les décalages sont comptés en cellules de la diagonale complète.
+    /// Avec [`Retention::Every`](crate::retention::Retention::Every)`(k)`, le motif ne contient
+    /// que les cellules échantillonnées et une période `p` est vue comme `ppcm(p, k)`.
     pub fn extract_patterns(&mut self, type_diagonal: DIAGONAL, offset_f: fn(usize) -> usize) -> Vec<(Vec<&Cell>, usize, usize)>
     {
         let mut result = Vec::new();
@@ -152,21 +189,23 @@ impl<'a> AutomatonAnalysis<'a> {
         for i in 0..diagonals.len() {
             if breaked { break; }
             let current_diagonal = diagonals.get(i).unwrap();
-            let offset = offset_f(i);
+            // Premier échantillon à partir de la cellule `offset_f(i)` de la diagonale complète.
+            let offset = offset_f(i).saturating_sub(current_diagonal.first).div_ceil(current_diagonal.stride);
 
             loop {
-                let pattern = self.find_pattern(current_diagonal.clone(), offset, period);
+                let pattern = self.find_pattern(current_diagonal.cells.clone(), offset, period);
 
                 match pattern {
                     (None, None, None) => {
                         period *= 2;
-                        if (offset + period) > current_diagonal.len() {
+                        if (offset + period) > current_diagonal.cells.len() {
                             breaked = true;
                             break;
                         }
                     }
                     (Some(p), Some(o), Some(t)) => {
-                        result.push((p, o, t));
+                        let stride = current_diagonal.stride;
+                        result.push((p, o * stride, current_diagonal.first + t * stride));
                         break;
                     }
                     (_, _, _) => {
@@ -204,3 +243,100 @@ impl<'a> AutomatonAnalysis<'a> {
         (None, None, None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retention::Retention;
+    use crate::row::Row;
+    use crate::rules::WolframRule;
+
+    fn automaton(retention: Retention) -> Automaton {
+        let mut config = vec![Cell::new(0); 61];
+        config[30] = Cell::new(1);
+        let mut automaton = Automaton::new(Row::new(config), Box::new(WolframRule::new(30)));
+        automaton.set_retention(retention).evolve(50);
+        automaton
+    }
+
+    #[test]
+    fn test_retention_policies() {
+        let reference = automaton(Retention::All);
+        let mut full = AutomatonAnalysis::new(&reference);
+        full.extract_diagonals(DIAGONAL::LEFT);
+        full.extract_diagonals(DIAGONAL::RIGHT);
+        let full_counts = full.rightmost_same_state(Cell::new(0));
+
+        for retention in [Retention::All, Retention::Last(30), Retention::Every(8), Retention::Nothing] {
+            let automaton = automaton(retention);
+            let mut analysis = AutomatonAnalysis::new(&automaton);
+            analysis.extract_diagonals(DIAGONAL::LEFT);
+            analysis.extract_diagonals(DIAGONAL::RIGHT);
+            assert_eq!(analysis.diagonals_left.len(), full.diagonals_left.len(), "{:?}", retention);
+
+            // Chaque diagonale est celle de l'évolution complète, restreinte aux lignes conservées.
+            // Chaque diagonale est celle de l'évolution complète, restreinte aux lignes conservées,
+            // et chaque cellule garde sa position dans la diagonale complète.
+            for (n, (left, right)) in analysis.diagonals_left.iter().zip(&analysis.diagonals_right).enumerate() {
+                let kept = |j: &usize| automaton.row(n + 1 + j).is_some() && retention.keeps(n + 1 + j);
+                let positions: Vec<usize> = (0..full.diagonals_left[n].cells.len()).filter(kept).collect();
+                for (sampled, complete) in [(left, &full.diagonals_left[n]), (right, &full.diagonals_right[n])] {
+                    assert_eq!(sampled.cells.len(), positions.len(), "{:?}, diagonal {}", retention, n + 1);
+                    for (j, cell) in sampled.cells.iter().enumerate() {
+                        assert_eq!(sampled.first + j * sampled.stride, positions[j], "{:?}, diagonal {}", retention, n + 1);
+                        assert_eq!(cell.state(), complete.cells[positions[j]].state(), "{:?}, diagonal {}", retention, n + 1);
+                    }
+                }
+            }
+            if retention == Retention::Nothing {
+                assert!(analysis.diagonals_left.iter().all(|diagonal| diagonal.cells.is_empty()));
+            }
+
+            // Les plages d'état 0 ne sont mesurées que sur les lignes conservées.
+            for (counter, generation) in analysis.rightmost_same_state(Cell::new(0)) {
+                let generation = generation as usize;
+                assert!(automaton.row(generation).is_some(), "{:?} at {}", retention, generation);
+                assert!(full_counts.get(&counter).is_some_and(|&first| first as usize <= generation));
+            }
+
+            // Chaque motif est lu sur les lignes conservées et se répète, en générations, sur
+            // la diagonale complète.
+            let strides: Vec<usize> = analysis.diagonals_left.iter().map(|diagonal| diagonal.stride).collect();
+            let found = analysis.extract_patterns(DIAGONAL::LEFT, |_| 0);
+            assert_eq!(found.is_empty(), retention == Retention::Nothing, "{:?}", retention);
+            for (n, (pattern, period, offset)) in found.iter().enumerate() {
+                let (complete, stride) = (&full.diagonals_left[n].cells, strides[n]);
+                assert!(automaton.row(n + 1 + offset).is_some() && retention.keeps(n + 1 + offset));
+                assert_eq!(pattern.len() * stride, *period, "{:?}, diagonal {}", retention, n + 1);
+                for (j, cell) in pattern.iter().enumerate() {
+                    assert_eq!(cell.state(), complete[offset + j * stride].state(), "{:?}, diagonal {}", retention, n + 1);
+                }
+                for t in (*offset..complete.len() - period).step_by(stride) {
+                    assert_eq!(complete[t].state(), complete[t + period].state(), "{:?}, diagonal {}", retention, n + 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sampled_periods() {
+        // Sur les diagonales droites de la règle 30, les périodes trouvées sont des puissances
+        // de deux au moins égales à 2 : un échantillonnage de pas 2 ne les change pas.
+        let patterns = |retention: Retention| {
+            let mut config = vec![Cell::new(0); 401];
+            config[200] = Cell::new(1);
+            let mut automaton = Automaton::new(Row::new(config), Box::new(WolframRule::new(30)));
+            automaton.set_retention(retention).evolve(200);
+            let mut analysis = AutomatonAnalysis::new(&automaton);
+            analysis.extract_diagonals(DIAGONAL::RIGHT);
+            analysis.extract_patterns(DIAGONAL::RIGHT, |_| 0).into_iter().map(|(_, period, offset)| (period, offset)).collect::<Vec<_>>()
+        };
+        let full = patterns(Retention::All);
+        let sampled = patterns(Retention::Every(2));
+        assert!(full.len() > 8);
+        assert_eq!(sampled.iter().map(|&(period, _)| period).collect::<Vec<_>>(), full.iter().map(|&(period, _)| period).collect::<Vec<_>>());
+        for (n, &(_, offset)) in sampled.iter().enumerate() {
+            assert!(Retention::Every(2).keeps(n + 1 + offset), "diagonal {}", n + 1);
+        }
+    }
+}
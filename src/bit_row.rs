@@ -0,0 +1,271 @@
+use crate::cell::Cell;
+use crate::row::Row;
+use crate::simd::Kernel;
+
+const WORD_BITS: usize = 64;
+
+/// Représente une ligne binaire compactée : 64 cellules par mot `u64`.
+///
+/// La cellule `i` est stockée dans le bit `i % 64` du mot `i / 64`.
+/// Les bits situés au-delà de `len` dans le dernier mot sont toujours à zéro.
+/// Une règle élémentaire est évaluée sur des mots entiers à l'aide de décalages
+/// et d'opérations booléennes, sans appel à `Rule::apply` par cellule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitRow {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitRow {
+    /// Crée une ligne de `len` cellules, toutes à l'état 0.
+    pub fn new(len: usize) -> BitRow {
+        Self {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+            len,
+        }
+    }
+
+    /// Compacte une `Row` binaire.
+    ///
+    /// Retourne `None` si une cellule a un état différent de 0 et 1.
+    pub fn from_row(row: &Row) -> Option<BitRow> {
+        let mut result = Self::new(row.len());
+        for i in 0..row.len() {
+            match row.get(i).unwrap().state() {
+                0 => {}
+                1 => result.words[i / WORD_BITS] |= 1 << (i % WORD_BITS),
+                _ => return None,
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Crée une ligne de `len` cellules à partir de ses mots ; les bits au-delà de `len` sont ignorés.
+    pub(crate) fn from_words(mut words: Vec<u64>, len: usize) -> BitRow {
+        words.resize(len.div_ceil(WORD_BITS), 0);
+        if let Some(last) = words.last_mut() {
+            *last &= tail_mask(len - (len - 1) / WORD_BITS * WORD_BITS);
+        }
+        Self { words, len }
+    }
+
+    /// Décompacte la ligne en une `Row` de cellules.
+    pub fn to_row(&self) -> Row {
+        let configuration = (0..self.len)
+            .map(|i| Cell::new(self.bit(i)))
+            .collect();
+        Row::new(configuration)
+    }
+
+    /// Retourne le nombre de cellules de la ligne.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Retourne `true` si la ligne ne contient aucune cellule.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Retourne l'état de la cellule à l'indice donné, ou `None` si l'indice est hors limites.
+    pub fn get(&self, index: usize) -> Option<u8> {
+        if index < self.len { Some(self.bit(index)) } else { None }
+    }
+
+    /// Modifie l'état (0 ou 1) de la cellule à l'indice donné.
+    ///
+    /// # Panics
+    /// Si l'indice est hors limites.
+    pub fn set(&mut self, index: usize, state: u8) {
+        assert!(index < self.len, "BitRow::set: index out of bounds");
+        let mask = 1 << (index % WORD_BITS);
+        if state & 1 == 1 {
+            self.words[index / WORD_BITS] |= mask;
+        } else {
+            self.words[index / WORD_BITS] &= !mask;
+        }
+    }
+
+    /// Retourne les mots compactés de la ligne.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Compte le nombre de cellules à l'état 1.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Calcule la ligne suivante selon la règle élémentaire `rule_number`.
+    ///
+    /// `left` et `right` sont les états des cellules fantômes situées
+    /// juste avant la première cellule et juste après la dernière.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let row = BitRow::from_row(&Row::new(vec![Cell::new(0), Cell::new(1), Cell::new(0)])).unwrap();
+    /// let next = row.next(30, 0, 0); // "###"
+    /// ```
+    pub fn next(&self, rule_number: u8, left: u8, right: u8) -> BitRow {
+        // Le noyau détecté est toujours disponible.
+        self.step(Kernel::detect(), rule_number, left, right)
+    }
+
+    /// Calcule la ligne suivante comme [`BitRow::next`], avec un noyau de calcul imposé.
+    ///
+    /// # Panics
+    /// Si le noyau n'est pas disponible sur le processeur courant.
+    pub fn next_with(&self, kernel: Kernel, rule_number: u8, left: u8, right: u8) -> BitRow {
+        assert!(kernel.is_available(), "BitRow: {:?} is not supported by this processor", kernel);
+        self.step(kernel, rule_number, left, right)
+    }
+
+    /// Calcule la ligne suivante avec un noyau dont la disponibilité a été vérifiée.
+    fn step(&self, kernel: Kernel, rule_number: u8, left: u8, right: u8) -> BitRow {
+        let mut result = Self::new(self.len);
+        if self.len == 0 {
+            return result;
+        }
+
+        let masks = rule_masks(rule_number);
+        let last = self.words.len() - 1;
+        let tail = self.len - last * WORD_BITS;
+        let carry_left = (left & 1) as u64;
+        let carry_right = ((right & 1) as u64) << (tail - 1);
+
+        kernel.step(&self.words, &mut result.words, &masks, carry_left, carry_right);
+        result.words[last] &= tail_mask(tail);
+        result
+    }
+
+    /// Retourne une copie de la ligne prolongée de `left` cellules à gauche et de
+    /// `right` cellules à droite, toutes à l'état `state`.
+    pub fn extended(&self, left: usize, right: usize, state: u8) -> BitRow {
+        let mut result = Self::new(self.len + left + right);
+        let offset = left / WORD_BITS;
+        let shift = left % WORD_BITS;
+
+        for (j, &word) in self.words.iter().enumerate() {
+            result.words[offset + j] |= word << shift;
+            if shift > 0 && offset + j + 1 < result.words.len() {
+                result.words[offset + j + 1] |= word >> (WORD_BITS - shift);
+            }
+        }
+
+        if state & 1 == 1 {
+            for i in (0..left).chain(left + self.len..result.len) {
+                result.set(i, 1);
+            }
+        }
+
+        result
+    }
+
+    fn bit(&self, index: usize) -> u8 {
+        ((self.words[index / WORD_BITS] >> (index % WORD_BITS)) & 1) as u8
+    }
+}
+
+/// Retourne le masque des `tail` premiers bits d'un mot.
+pub(crate) fn tail_mask(tail: usize) -> u64 {
+    if tail >= WORD_BITS { u64::MAX } else { (1 << tail) - 1 }
+}
+
+/// Développe le numéro d'une règle élémentaire en 8 masques :
+/// `masks[i]` vaut `u64::MAX` si le bit `i` du numéro est à 1, sinon `0`.
+pub(crate) fn rule_masks(rule_number: u8) -> [u64; 8] {
+    let mut masks = [0; 8];
+    for (i, mask) in masks.iter_mut().enumerate() {
+        if (rule_number >> i) & 1 == 1 {
+            *mask = u64::MAX;
+        }
+    }
+    masks
+}
+
+/// Évalue une règle élémentaire sur 64 cellules à la fois.
+///
+/// La table de la règle est parcourue comme un multiplexeur à trois niveaux
+/// (droite, centre, puis gauche), ce qui évite tout branchement.
+#[inline(always)]
+pub(crate) fn eval_word(masks: &[u64; 8], l: u64, c: u64, r: u64) -> u64 {
+    let mux = |s: u64, one: u64, zero: u64| (s & one) | (!s & zero);
+
+    let g00 = mux(r, masks[1], masks[0]);
+    let g01 = mux(r, masks[3], masks[2]);
+    let g10 = mux(r, masks[5], masks[4]);
+    let g11 = mux(r, masks[7], masks[6]);
+
+    let h0 = mux(c, g01, g00);
+    let h1 = mux(c, g11, g10);
+
+    mux(l, h1, h0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Rule, WolframRule};
+
+    fn row_from_binary(binary: &str) -> Row {
+        Row::new(binary.chars().map(|c| Cell::new(c.to_digit(10).unwrap() as u8)).collect())
+    }
+
+    fn naive_next(row: &Row, rule: &dyn Rule, left: u8, right: u8) -> Row {
+        let n = row.len();
+        let state = |i: isize| -> u8 {
+            if i < 0 { left } else if i as usize >= n { right } else { row.get(i as usize).unwrap().state() }
+        };
+        let configuration = (0..n as isize)
+            .map(|i| Cell::new(rule.apply(&[state(i - 1), state(i), state(i + 1)])))
+            .collect();
+        Row::new(configuration)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let row = row_from_binary("0110100111010101110010101010110101011101011010101010001011101010101110101");
+        let packed = BitRow::from_row(&row).unwrap();
+        assert_eq!(packed.to_row().to_string(), row.to_string());
+        assert_eq!(packed.count_ones(), row.to_string().matches('#').count());
+
+        assert!(BitRow::from_row(&Row::new(vec![Cell::new(2)])).is_none());
+    }
+
+    #[test]
+    fn test_extended() {
+        let row = row_from_binary("1011001110001111010101011100101010110010101111010101000111010101011101");
+        let packed = BitRow::from_row(&row).unwrap();
+        for (left, right, state) in [(0, 0, 0), (1, 1, 1), (3, 70, 0), (64, 1, 1), (65, 129, 1)] {
+            let fill = if state == 1 { "#" } else { "." };
+            let expected = format!("{}{}{}", fill.repeat(left), row.to_string(), fill.repeat(right));
+            assert_eq!(packed.extended(left, right, state).to_row().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_every_elementary_rule_matches_naive() {
+        let mut pattern = String::new();
+        let mut seed: u64 = 0x9E37_79B9_7F4A_7C15;
+        for _ in 0..200 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            pattern.push(if seed & 1 == 1 { '1' } else { '0' });
+        }
+
+        for len in [1, 63, 64, 65, 128, 200] {
+            let row = row_from_binary(&pattern[..len]);
+            let packed = BitRow::from_row(&row).unwrap();
+            for rule_number in 0..=255u8 {
+                let rule = WolframRule::new(rule_number);
+                for (left, right) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                    let expected = naive_next(&row, &rule, left, right);
+                    let actual = packed.next(rule_number, left, right).to_row();
+                    assert_eq!(actual.to_string(), expected.to_string(), "rule {} len {}", rule_number, len);
+                }
+            }
+        }
+    }
+}
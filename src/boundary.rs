@@ -0,0 +1,143 @@
+/// Condition aux bords d'une ligne de taille finie.
+///
+/// Elle détermine l'état des cellules fantômes situées avant la première
+/// cellule (à gauche) et après la dernière (à droite) lors du calcul
+/// de la ligne suivante.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum Boundary {
+    /// Anneau : la voisine gauche de la première cellule est la dernière, et inversement.
+    #[default]
+    Periodic,
+    /// Les cellules fantômes ont toujours l'état donné (`Fixed(0)`, `Fixed(1)`, ...).
+    Fixed(u8),
+    /// Miroir : les cellules fantômes recopient les cellules du bord,
+    /// la voisine gauche de la première cellule est donc elle-même.
+    Reflecting,
+    /// Les cellules fantômes suivent une séquence fournie par l'utilisateur.
+    ///
+    /// À l'itération `t`, le bord gauche vaut `left[t % left.len()]` et le bord
+    /// droit `right[t % right.len()]`. Une séquence vide équivaut à un bord à 0.
+    Sequence { left: Vec<u8>, right: Vec<u8> },
+}
+
+/// Côté d'une ligne.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Boundary {
+    /// Retourne l'état de la cellule fantôme à la distance `distance` (à partir de 1)
+    /// du côté `side` d'une ligne de `len` cellules, à l'itération `time`.
+    ///
+    /// `cell` donne l'état de la cellule d'indice `i` de la ligne, ce qui permet
+    /// d'utiliser la même condition pour une `Row` et une `BitRow`.
+    pub fn ghost(&self, side: Side, distance: usize, len: usize, time: usize, cell: impl Fn(usize) -> u8) -> u8 {
+        match self {
+            Boundary::Fixed(state) => *state,
+            Boundary::Sequence { left, right } => {
+                let sequence = match side {
+                    Side::Left => left,
+                    Side::Right => right,
+                };
+                if sequence.is_empty() { 0 } else { sequence[time % sequence.len()] }
+            }
+            _ if len == 0 => 0,
+            Boundary::Periodic => match side {
+                Side::Left => cell((len - distance % len) % len),
+                Side::Right => cell((distance - 1) % len),
+            },
+            Boundary::Reflecting => {
+                let folded = (distance - 1) % (2 * len);
+                let index = if folded < len { folded } else { 2 * len - 1 - folded };
+                match side {
+                    Side::Left => cell(index),
+                    Side::Right => cell(len - 1 - index),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::Automaton;
+    use crate::cell::Cell;
+    use crate::row::Row;
+    use crate::rules::WolframRule;
+
+    fn row_from_binary(binary: &str) -> Row {
+        Row::new(binary.chars().map(|c| Cell::new(c.to_digit(10).unwrap() as u8)).collect())
+    }
+
+    fn to_binary(row: &Row) -> String {
+        (0..row.len()).map(|i| row.get(i).unwrap().to_string()).collect()
+    }
+
+    /// Vérifie une étape sur "10010", par `Row::next_with_boundary` et par `Automaton`.
+    fn check(boundary: Boundary, rule_number: u8, expected: &str) {
+        let row = row_from_binary("10010");
+        let rule = WolframRule::new(rule_number);
+        assert_eq!(to_binary(&row.next_with_boundary(&rule, &boundary)), expected, "{:?}", boundary);
+
+        let mut automaton = Automaton::new(row, Box::new(WolframRule::new(rule_number)));
+        automaton.set_boundary(boundary.clone());
+        automaton.next();
+        assert_eq!(to_binary(automaton.grid().last().unwrap()), expected, "{:?}", boundary);
+    }
+
+    #[test]
+    fn test_periodic() {
+        check(Boundary::Periodic, 30, "11110");
+        check(Boundary::Periodic, 90, "01100");
+    }
+
+    #[test]
+    fn test_fixed_zero() {
+        check(Boundary::Fixed(0), 30, "11111");
+        check(Boundary::Fixed(0), 90, "01101");
+    }
+
+    #[test]
+    fn test_fixed_one() {
+        check(Boundary::Fixed(1), 30, "01110");
+        check(Boundary::Fixed(1), 90, "11100");
+    }
+
+    #[test]
+    fn test_reflecting() {
+        check(Boundary::Reflecting, 30, "01111");
+        check(Boundary::Reflecting, 90, "11101");
+    }
+
+    #[test]
+    fn test_sequence() {
+        let boundary = Boundary::Sequence { left: vec![0, 1], right: vec![1, 1, 0] };
+        check(boundary.clone(), 30, "11110");
+        check(boundary.clone(), 90, "01100");
+
+        for (rule_number, expected) in [(30, "00000"), (90, "01111")] {
+            let mut automaton = Automaton::new(row_from_binary("10010"), Box::new(WolframRule::new(rule_number)));
+            automaton.set_boundary(boundary.clone());
+            automaton.evolve(2);
+            assert_eq!(to_binary(automaton.grid().last().unwrap()), expected);
+
+            let rule = WolframRule::new(rule_number);
+            let row = row_from_binary("10010").next_with_boundary(&rule, &boundary);
+            assert_eq!(to_binary(&row.next_with_boundary(&rule, &boundary)), expected);
+        }
+    }
+
+    #[test]
+    fn test_ghost_beyond_row_length() {
+        let cells = [1, 2, 3];
+        let cell = |i: usize| cells[i];
+        assert_eq!(Boundary::Periodic.ghost(Side::Left, 4, 3, 0, cell), 3);
+        assert_eq!(Boundary::Periodic.ghost(Side::Right, 4, 3, 0, cell), 1);
+        assert_eq!(Boundary::Reflecting.ghost(Side::Left, 2, 3, 0, cell), 2);
+        assert_eq!(Boundary::Reflecting.ghost(Side::Left, 4, 3, 0, cell), 3);
+        assert_eq!(Boundary::Reflecting.ghost(Side::Right, 5, 3, 0, cell), 2);
+    }
+}
@@ -1,4 +1,5 @@
 use std::ops::{BitAnd, BitOr, BitXor, Not};
+use crate::palette::{Palette, DEFAULT_SYMBOLS};
 
 /// Représente une cellule dans un automate cellulaire.
 ///
@@ -18,6 +19,8 @@ impl Not for Cell {
     /// Retourne une nouvelle cellule avec l'état inversé :
     /// - 0 devient 1
     /// - tout autre état devient 0
+    ///
+    /// Cet opérateur suppose des états binaires ; pour `k` états, voir [`Cell::complement`].
     fn not(self) -> Self::Output {
         if self.state == 0 { Cell::new(1) } else { Cell::new(0) }
     }
@@ -95,20 +98,55 @@ impl Cell {
         self.state = state;
     }
 
+    /// Retourne le complément de la cellule pour un automate à `colours` états :
+    /// l'état `s` devient `colours - 1 - s`.
+    ///
+    /// # Panics
+    /// Si l'état de la cellule n'est pas inférieur à `colours`.
+    ///
+    /// # Exemple
+    /// ```text
+    /// assert_eq!(Cell::new(0).complement(3), Cell::new(2));
+    /// ```
+    pub fn complement(&self, colours: u8) -> Cell {
+        assert!(self.state < colours, "Cell: state {} is out of range for {} colours", self.state, colours);
+        Cell::new(colours - 1 - self.state)
+    }
+
     /// Retourne un caractère représentant visuellement l'état de la cellule.
     ///
     /// - `.` pour l'état 0
     /// - `#` pour l'état 1
+    /// - `+`, `*`, `o`, `@`, `%`, `&`, `=`, `~` pour les états 2 à 9
     /// - `?` pour tout autre état
     pub fn display(&self) -> char {
-        match self.state {
-            0 => '.',
-            1 => '#',
-            _ => '?',
-        }
+        DEFAULT_SYMBOLS.get(self.state as usize).copied().unwrap_or('?')
+    }
+
+    /// Retourne le rendu de la cellule selon une palette de symboles ou de couleurs.
+    pub fn display_with(&self, palette: &Palette) -> String {
+        palette.render(self.state)
     }
 
     pub fn to_string(&self) -> String {
         self.state.to_string()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complement() {
+        assert_eq!(Cell::new(0).complement(3).state(), 2);
+        assert_eq!(Cell::new(2).complement(3).state(), 0);
+        assert_eq!(Cell::new(1).complement(2).state(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_complement_out_of_range() {
+        Cell::new(3).complement(3);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::boundary::Boundary;
+use crate::cell::Cell;
+use crate::row::Row;
+use crate::rules::Rule;
+
+/// Nombre maximal d'entrées de la table d'une règle compilée (`k^window`).
+const MAX_TABLE_SIZE: usize = 1 << 20;
+
+/// Règle déterministe précalculée sous forme de table.
+///
+/// La table est indexée comme le numéro de Wolfram : le voisinage, lu de gauche à droite,
+/// est un entier en base `k`. En parcourant une ligne, l'indice d'un voisinage se déduit
+/// de celui du voisinage précédent par un décalage, sans appel à la règle d'origine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledRule {
+    table: Vec<u8>,
+    window: usize,
+    left_reach: usize,
+    right_reach: usize,
+    colours: u8,
+}
+
+impl CompiledRule {
+    /// Compile une règle en sondant tous ses voisinages.
+    ///
+    /// Retourne `None` si la règle n'est pas déterministe ou si sa table dépasse
+    /// 2^20 entrées.
+    pub fn compile(rule: &dyn Rule) -> Option<CompiledRule> {
+        let size = (rule.colours() as usize)
+            .checked_pow(rule.window() as u32)
+            .filter(|&size| size <= MAX_TABLE_SIZE)?;
+        let table = rule.lookup_table()?;
+        debug_assert_eq!(table.len(), size);
+
+        Some(Self {
+            table,
+            window: rule.window(),
+            left_reach: rule.left_reach(),
+            right_reach: rule.right_reach(),
+            colours: rule.colours(),
+        })
+    }
+
+    /// Retourne le numéro de la règle si elle est élémentaire (binaire, de rayon 1).
+    pub fn elementary_number(&self) -> Option<u8> {
+        if self.window != 3 || self.colours != 2 {
+            return None;
+        }
+        Some(self.table.iter().rev().fold(0, |number, &state| (number << 1) | state))
+    }
+
+    /// Calcule la ligne suivante avec la condition aux bords donnée,
+    /// comme [`Row::next_with_boundary`].
+    pub fn next_row(&self, row: &Row, boundary: &Boundary) -> Row {
+        let padded = row.padded_states(self.left_reach, self.right_reach, boundary);
+        let mut next = Row::new(roll(&padded, self.window, self.colours, &self.table));
+        next.set_iteration(row.iteration() + 1);
+        next
+    }
+}
+
+impl Rule for CompiledRule {
+    fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        let k = self.colours as usize;
+        self.table[neighbourhood.iter().fold(0, |index, &state| index * k + state as usize)]
+    }
+
+    fn window(&self) -> usize {
+        self.window
+    }
+
+    fn left_reach(&self) -> usize {
+        self.left_reach
+    }
+
+    fn right_reach(&self) -> usize {
+        self.right_reach
+    }
+
+    fn colours(&self) -> u8 {
+        self.colours
+    }
+
+    fn lookup_table(&self) -> Option<Vec<u8>> {
+        Some(self.table.clone())
+    }
+}
+
+/// Applique une table à chaque fenêtre de `window` états consécutifs, en faisant glisser
+/// l'indice du voisinage d'une cellule à la suivante.
+fn roll(states: &[u8], window: usize, colours: u8, table: &[u8]) -> Vec<Cell> {
+    let k = colours as usize;
+    let mut index = 0;
+    for &state in &states[..window - 1] {
+        index = index * k + state as usize;
+    }
+
+    states[window - 1..]
+        .iter()
+        .map(|&state| {
+            index = (index * k + state as usize) % table.len();
+            Cell::new(table[index])
+        })
+        .collect()
+}
+
+/// Table d'une règle élémentaire qui saute plusieurs générations d'un coup.
+///
+/// Après `steps` itérations, l'état d'une cellule ne dépend que des `2 * steps + 1` cellules
+/// centrées sur elle : la table associe à chacune de ces fenêtres l'état de la cellule centrale
+/// `steps` générations plus tard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiStepTable {
+    rule_number: u8,
+    steps: usize,
+    table: Vec<u8>,
+}
+
+impl MultiStepTable {
+    /// Construit la table de la règle élémentaire `rule_number` pour `steps` générations.
+    ///
+    /// # Panics
+    /// Si `steps` n'est pas compris entre 1 et 10 (la table a `2^(2 * steps + 1)` entrées).
+    pub fn new(rule_number: u8, steps: usize) -> MultiStepTable {
+        assert!((1..=10).contains(&steps), "MultiStepTable: steps should be between 1 and 10");
+        let window = 2 * steps + 1;
+        let table = (0..1usize << window)
+            .map(|index| {
+                let mut cells: Vec<u8> = (0..window).rev().map(|bit| ((index >> bit) & 1) as u8).collect();
+                for _ in 0..steps {
+                    cells = cells
+                        .windows(3)
+                        .map(|n| (rule_number >> ((n[0] << 2) | (n[1] << 1) | n[2])) & 1)
+                        .collect();
+                }
+                cells[0]
+            })
+            .collect();
+
+        Self { rule_number, steps, table }
+    }
+
+    /// Retourne le numéro de la règle élémentaire.
+    pub fn rule_number(&self) -> u8 {
+        self.rule_number
+    }
+
+    /// Retourne le nombre de générations sautées.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Retourne l'état de la cellule centrale `steps` générations plus tard, pour une fenêtre
+    /// de `2 * steps + 1` cellules lue comme un entier binaire, la cellule de gauche en poids fort.
+    pub fn apply(&self, window: usize) -> u8 {
+        self.table[window]
+    }
+
+    /// Calcule la ligne `steps` générations plus tard, la ligne étant un anneau.
+    ///
+    /// Seule la condition périodique se prête au saut : avec elle, les cellules fantômes
+    /// évoluent comme les cellules de la ligne dont elles sont la copie.
+    pub fn next_row(&self, row: &Row) -> Row {
+        let padded = row.padded_states(self.steps, self.steps, &Boundary::Periodic);
+        let mut next = Row::new(roll(&padded, 2 * self.steps + 1, 2, &self.table));
+        next.set_iteration(row.iteration() + self.steps);
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::natural::Natural;
+    use crate::rules::{Rule30, TotalisticRule, WolframRule};
+    use crate::stochastic::NoisyRule;
+
+    fn random_row(len: usize, seed: u64, colours: u8) -> Row {
+        let mut random = crate::random::Random::new(seed);
+        Row::new((0..len).map(|_| Cell::new(random.below(colours as u64) as u8)).collect())
+    }
+
+    #[test]
+    fn test_compiled_matches_rule() {
+        let rules: Vec<(Box<dyn Rule>, u8)> = vec![
+            (Box::new(Rule30), 2),
+            (Box::new(WolframRule::with_window(0x5A3C, 4)), 2),
+            (Box::new(WolframRule::with_window(6, 2)), 2),
+            (Box::new(TotalisticRule::new(&Natural::from(1599u64), 3, 3)), 3),
+            (Box::new(WolframRule::with_colours(&"987654321987654321".parse().unwrap(), 3, 4)), 3),
+        ];
+        let boundaries = [
+            Boundary::Periodic,
+            Boundary::Fixed(1),
+            Boundary::Reflecting,
+            Boundary::Sequence { left: vec![0, 1], right: vec![1, 1, 0] },
+        ];
+
+        for (rule, colours) in &rules {
+            let compiled = CompiledRule::compile(rule.as_ref()).unwrap();
+            for boundary in &boundaries {
+                let mut row = random_row(37, *colours as u64, *colours);
+                for _ in 0..10 {
+                    let expected = row.next_with_boundary(rule.as_ref(), boundary);
+                    let actual = compiled.next_row(&row, boundary);
+                    assert_eq!(actual.to_string(), expected.to_string());
+                    assert_eq!(actual.iteration(), expected.iteration());
+                    row = expected;
+                }
+            }
+        }
+
+        assert_eq!(CompiledRule::compile(&Rule30).unwrap().elementary_number(), Some(30));
+        assert!(CompiledRule::compile(&NoisyRule::new(Box::new(Rule30), 0.1, 1)).is_none());
+    }
+
+    #[test]
+    fn test_multi_step_matches_single_steps() {
+        for rule_number in [30, 110, 90, 184, 54] {
+            let rule = WolframRule::new(rule_number);
+            for steps in 1..=5 {
+                let table = MultiStepTable::new(rule_number, steps);
+                for len in [3, 29, 64] {
+                    let row = random_row(len, rule_number as u64 + len as u64, 2);
+                    let mut expected = row.clone();
+                    for _ in 0..steps {
+                        expected = expected.next(&rule);
+                    }
+                    let actual = table.next_row(&row);
+                    assert_eq!(actual.to_string(), expected.to_string(), "rule {} in {} steps on {} cells", rule_number, steps, len);
+                    assert_eq!(actual.iteration(), steps);
+                }
+            }
+        }
+    }
+}
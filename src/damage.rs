@@ -0,0 +1,269 @@
+use crate::automaton::Automaton;
+use crate::boundary::Boundary;
+use crate::cell::Cell;
+use crate::compiled::CompiledRule;
+use crate::retention::Retention;
+use crate::row::Row;
+use crate::rules::Rule;
+
+/// Différence entre les deux évolutions d'une [`DamageSpreading`] à une génération.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Damage {
+    generation: usize,
+    hamming: usize,
+    /// Colonnes extrêmes des cellules qui diffèrent, relatives au défaut initial.
+    cone: Option<(i64, i64)>,
+}
+
+impl Damage {
+    /// Retourne la génération mesurée (0 pour les lignes initiales).
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Retourne la distance de Hamming : le nombre de cellules qui diffèrent.
+    pub fn hamming(&self) -> usize {
+        self.hamming
+    }
+
+    /// Retourne la colonne, relative au défaut, de la cellule différente la plus à gauche,
+    /// ou `None` si le dommage a disparu.
+    pub fn left(&self) -> Option<i64> {
+        self.cone.map(|(left, _)| left)
+    }
+
+    /// Retourne la colonne, relative au défaut, de la cellule différente la plus à droite,
+    /// ou `None` si le dommage a disparu.
+    pub fn right(&self) -> Option<i64> {
+        self.cone.map(|(_, right)| right)
+    }
+
+    /// Retourne `true` si les deux lignes sont identiques.
+    pub fn is_healed(&self) -> bool {
+        self.cone.is_none()
+    }
+
+    /// Retourne les exposants de Lyapunov gauche et droit estimés à cette génération :
+    /// les vitesses moyennes auxquelles les bords du cône de différence s'éloignent du défaut,
+    /// vers la gauche et vers la droite. Un bord qui recule donne une vitesse négative.
+    ///
+    /// Retourne `None` à la génération 0 ou si le dommage a disparu.
+    pub fn lyapunov_exponents(&self) -> Option<(f64, f64)> {
+        let (left, right) = self.cone.filter(|_| self.generation > 0)?;
+        let time = self.generation as f64;
+        Some((-left as f64 / time, right as f64 / time))
+    }
+}
+
+/// Propagation d'un défaut : deux automates de même règle, dont les premières lignes
+/// ne diffèrent qu'en une cellule, évoluent côte à côte.
+///
+/// À chaque génération, la distance de Hamming et les bords du cône de différence sont
+/// enregistrés. Les deux automates évoluent avec une copie de la même [`CompiledRule`] et ne conservent
+/// que leur ligne courante.
+///
+/// Sur une grille de largeur fixe, les mesures n'ont de sens que tant que le cône
+/// n'a pas atteint les bords de la ligne.
+///
+/// # Exemple
+/// ```text
+/// let mut damage = DamageSpreading::new(row, 500, &WolframRule::new(30)).unwrap();
+/// damage.evolve(400);
+/// let (left, right) = damage.lyapunov_exponents().unwrap();
+/// ```
+pub struct DamageSpreading {
+    original: Automaton,
+    perturbed: Automaton,
+    /// Colonne (signée) du défaut dans le repère des automates.
+    defect: i64,
+    history: Vec<Damage>,
+}
+
+impl DamageSpreading {
+    /// Prépare la comparaison sur une grille de largeur fixe : la cellule `defect` de la ligne
+    /// perturbée passe à l'état suivant, modulo le nombre d'états de la règle.
+    ///
+    /// Retourne `None` si la règle n'est pas déterministe.
+    ///
+    /// # Panics
+    /// Si `defect` est hors de la ligne.
+    pub fn new(first_row: Row, defect: usize, rule: &dyn Rule) -> Option<DamageSpreading> {
+        let compiled = CompiledRule::compile(rule)?;
+        let perturbed = perturb(&first_row, defect, rule.colours());
+        let original = Automaton::new(first_row, Box::new(compiled.clone()));
+        let perturbed = Automaton::new(perturbed, Box::new(compiled));
+        Some(Self::with_automata(original, perturbed, defect))
+    }
+
+    /// Prépare la comparaison sur des lignes qui grandissent avec le cône de lumière,
+    /// sur un fond quiescent (voir [`Automaton::new_light_cone`]).
+    ///
+    /// Retourne `None` si la règle n'est pas déterministe.
+    ///
+    /// # Panics
+    /// Si `defect` est hors de la ligne.
+    pub fn new_light_cone(first_row: Row, origin: usize, defect: usize, rule: &dyn Rule) -> Option<DamageSpreading> {
+        let compiled = CompiledRule::compile(rule)?;
+        let perturbed = perturb(&first_row, defect, rule.colours());
+        let original = Automaton::new_light_cone(first_row, origin, Box::new(compiled.clone()));
+        let perturbed = Automaton::new_light_cone(perturbed, origin, Box::new(compiled));
+        Some(Self::with_automata(original, perturbed, defect))
+    }
+
+    fn with_automata(mut original: Automaton, mut perturbed: Automaton, defect: usize) -> Self {
+        original.set_retention(Retention::Nothing);
+        perturbed.set_retention(Retention::Nothing);
+        let defect = original.left_edge(0) + defect as i64;
+        let mut spreading = Self { original, perturbed, defect, history: vec![] };
+        let damage = spreading.measure();
+        spreading.history.push(damage);
+        spreading
+    }
+
+    /// Change la condition aux bords des deux automates.
+    pub fn set_boundary(&mut self, boundary: Boundary) -> &mut Self {
+        self.original.set_boundary(boundary.clone());
+        self.perturbed.set_boundary(boundary);
+        self
+    }
+
+    /// Calcule la génération suivante des deux automates et retourne sa mesure.
+    pub fn step(&mut self) -> Damage {
+        self.original.next();
+        self.perturbed.next();
+        let damage = self.measure();
+        self.history.push(damage);
+        damage
+    }
+
+    /// Calcule `steps` générations et retourne les mesures de toutes les générations calculées
+    /// depuis la création.
+    pub fn evolve(&mut self, steps: usize) -> &[Damage] {
+        for _ in 0..steps {
+            self.step();
+        }
+        &self.history
+    }
+
+    /// Retourne les mesures de chaque génération, à partir de la génération 0.
+    pub fn history(&self) -> &[Damage] {
+        &self.history
+    }
+
+    /// Retourne la mesure de la dernière génération calculée.
+    pub fn last(&self) -> Damage {
+        *self.history.last().unwrap()
+    }
+
+    /// Retourne les exposants de Lyapunov gauche et droit estimés à la dernière génération
+    /// (voir [`Damage::lyapunov_exponents`]).
+    pub fn lyapunov_exponents(&self) -> Option<(f64, f64)> {
+        self.last().lyapunov_exponents()
+    }
+
+    /// Retourne la ligne courante de l'évolution non perturbée.
+    pub fn original(&self) -> &Row {
+        self.original.current()
+    }
+
+    /// Retourne la ligne courante de l'évolution perturbée.
+    pub fn perturbed(&self) -> &Row {
+        self.perturbed.current()
+    }
+
+    fn measure(&self) -> Damage {
+        let (original, perturbed) = (self.original.current(), self.perturbed.current());
+        let generation = original.iteration();
+        let left_edge = self.original.left_edge(generation) - self.defect;
+        let differences: Vec<i64> = (0..original.len())
+            .filter(|&i| original.get(i).unwrap().state() != perturbed.get(i).unwrap().state())
+            .map(|i| left_edge + i as i64)
+            .collect();
+        Damage {
+            generation,
+            hamming: differences.len(),
+            cone: differences.first().map(|&left| (left, *differences.last().unwrap())),
+        }
+    }
+}
+
+/// Retourne une copie de la ligne dont la cellule `defect` passe à l'état suivant.
+fn perturb(row: &Row, defect: usize, colours: u8) -> Row {
+    assert!(defect < row.len(), "DamageSpreading: the defect should lie within the row");
+    let states = (0..row.len()).map(|i| {
+        let state = row.get(i).unwrap().state();
+        Cell::new(if i == defect { (state + 1) % colours } else { state })
+    });
+    Row::new(states.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use crate::rules::WolframRule;
+
+    fn random_row(width: usize, seed: u64) -> Row {
+        let mut random = Random::new(seed);
+        Row::new((0..width).map(|_| Cell::new(random.below(2) as u8)).collect())
+    }
+
+    #[test]
+    fn test_rule_30_is_asymmetric() {
+        let mut damage = DamageSpreading::new(random_row(1001, 30), 500, &WolframRule::new(30)).unwrap();
+        assert_eq!(damage.last(), Damage { generation: 0, hamming: 1, cone: Some((0, 0)) });
+        assert_eq!(damage.lyapunov_exponents(), None);
+
+        // La règle 30 est permutative en sa voisine gauche : le dommage avance d'une cellule
+        // vers la droite à chaque génération, mais ne s'étend que lentement vers la gauche.
+        for (t, step) in damage.evolve(400).iter().enumerate() {
+            assert_eq!(step.generation(), t);
+            assert_eq!(step.right(), Some(t as i64));
+            assert!(step.left().unwrap() <= 0);
+        }
+        let (left, right) = damage.lyapunov_exponents().unwrap();
+        assert_eq!(right, 1.0);
+        assert!((0.15..0.35).contains(&left), "left exponent {}", left);
+    }
+
+    #[test]
+    fn test_additive_and_healing_rules() {
+        // Pour la règle 90, le dommage est la ligne `t` du triangle de Pascal modulo 2.
+        let mut damage = DamageSpreading::new(random_row(301, 90), 150, &WolframRule::new(90)).unwrap();
+        for step in damage.evolve(100) {
+            let t = step.generation();
+            assert_eq!(step.hamming(), 1 << t.count_ones());
+            assert_eq!((step.left(), step.right()), (Some(-(t as i64)), Some(t as i64)));
+        }
+        assert_eq!(damage.lyapunov_exponents(), Some((1.0, 1.0)));
+
+        let mut damage = DamageSpreading::new(random_row(41, 0), 20, &WolframRule::new(204)).unwrap();
+        assert_eq!(damage.evolve(10).last().unwrap().left(), Some(0));
+        assert_eq!(damage.lyapunov_exponents(), Some((0.0, 0.0)));
+
+        let mut damage = DamageSpreading::new(random_row(41, 0), 20, &WolframRule::new(0)).unwrap();
+        assert!(damage.step().is_healed());
+        assert_eq!(damage.lyapunov_exponents(), None);
+    }
+
+    #[test]
+    fn test_light_cone() {
+        // Sur un fond nul, retirer la graine fait de tout le motif de la règle 30 un dommage.
+        let seed = Row::new(vec![Cell::new(1)]);
+        let rule = WolframRule::new(30);
+        let mut damage = DamageSpreading::new_light_cone(seed.clone(), 0, 0, &rule).unwrap();
+        let mut automaton = Automaton::new_light_cone(seed, 0, Box::new(rule));
+        for _ in 0..50 {
+            let step = damage.step();
+            automaton.next();
+            let t = step.generation() as i64;
+            assert_eq!((step.left(), step.right()), (Some(-t), Some(t)));
+            assert_eq!(damage.original().to_string(), automaton.current().to_string());
+            assert_eq!(step.hamming(), automaton.current().to_string().matches('#').count());
+        }
+
+        let mut damage = DamageSpreading::new(random_row(21, 1), 0, &WolframRule::new(30)).unwrap();
+        damage.set_boundary(Boundary::Fixed(0));
+        assert_eq!(damage.evolve(3).last().unwrap().right(), Some(3));
+    }
+}
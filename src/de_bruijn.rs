@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+use crate::rules::Rule;
+
+/// Graphe de de Bruijn d'une règle déterministe à `k` états sur une fenêtre de `w` cellules.
+///
+/// Les sommets sont les mots de `w - 1` états et les arêtes les voisinages de `w` états :
+/// l'arête `u -> v` relie le préfixe et le suffixe d'un voisinage, et porte l'état que
+/// la règle lui associe. Une ligne de `n` cellules qui admet un antécédent correspond alors
+/// à un chemin de `n` arêtes dont les étiquettes forment la ligne.
+///
+/// Sommets et arêtes sont numérotés comme les voisinages dans le numéro de Wolfram :
+/// le mot, lu de gauche à droite, est un entier en base `k`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeBruijnGraph {
+    table: Vec<u8>,
+    window: usize,
+    right_reach: usize,
+    colours: u8,
+}
+
+impl DeBruijnGraph {
+    /// Construit le graphe d'une règle, ou retourne `None` si la règle n'est pas déterministe.
+    pub fn new(rule: &dyn Rule) -> Option<DeBruijnGraph> {
+        let table = rule.lookup_table()?;
+        Some(Self {
+            table,
+            window: rule.window(),
+            right_reach: rule.right_reach(),
+            colours: rule.colours(),
+        })
+    }
+
+    /// Retourne le nombre de sommets, `k^(w - 1)`.
+    pub fn node_count(&self) -> usize {
+        self.table.len() / self.colours as usize
+    }
+
+    /// Retourne le nombre d'états `k`.
+    pub fn colours(&self) -> u8 {
+        self.colours
+    }
+
+    /// Retourne le nombre de cellules `w` d'un voisinage.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Retourne le nombre de voisines situées à droite de la cellule dans un voisinage.
+    pub fn right_reach(&self) -> usize {
+        self.right_reach
+    }
+
+    /// Retourne les arêtes issues d'un sommet, sous la forme `(dernier état, sommet d'arrivée,
+    /// étiquette)`, par dernier état croissant.
+    pub fn edges(&self, node: usize) -> impl Iterator<Item = (u8, usize, u8)> + '_ {
+        let k = self.colours as usize;
+        (0..self.colours).map(move |state| {
+            let edge = node * k + state as usize;
+            (state, edge % self.node_count(), self.table[edge])
+        })
+    }
+
+    /// Retourne les sommets atteints depuis `node` par une arête d'étiquette `label`.
+    pub fn successors(&self, node: usize, label: u8) -> impl Iterator<Item = usize> + '_ {
+        self.edges(node).filter(move |&(_, _, l)| l == label).map(|(_, target, _)| target)
+    }
+
+    /// Retourne l'ensemble des sommets atteints depuis l'ensemble `set` (un bit par sommet)
+    /// par une arête d'étiquette `label`.
+    pub(crate) fn follow(&self, set: u64, label: u8) -> u64 {
+        (0..self.node_count())
+            .filter(|&node| (set >> node) & 1 == 1)
+            .flat_map(|node| self.successors(node, label))
+            .fold(0, |next, target| next | (1 << target))
+    }
+
+    /// Retourne le mot de `w - 1` états d'un sommet, de gauche à droite.
+    pub fn states(&self, node: usize) -> Vec<u8> {
+        let k = self.colours as usize;
+        let mut rest = node;
+        let mut states = vec![0; self.window - 1];
+        for state in states.iter_mut().rev() {
+            *state = (rest % k) as u8;
+            rest /= k;
+        }
+        states
+    }
+
+    /// Retourne `true` si la règle est surjective sur la ligne infinie : tout motif fini
+    /// a un antécédent, il n'existe aucun motif orphelin.
+    ///
+    /// Les ensembles de sommets où aboutissent les chemins étiquetés par un motif sont explorés
+    /// depuis l'ensemble de tous les sommets (construction des sous-ensembles) ; la règle
+    /// est surjective si l'ensemble vide n'est jamais atteint.
+    ///
+    /// # Panics
+    /// Si le graphe a plus de 64 sommets.
+    pub fn is_surjective(&self) -> bool {
+        let nodes = self.node_count();
+        assert!(nodes <= 64, "DeBruijnGraph: too many nodes for the subset construction");
+        let everything = if nodes == 64 { u64::MAX } else { (1 << nodes) - 1 };
+
+        let mut seen = HashSet::from([everything]);
+        let mut stack = vec![everything];
+        while let Some(set) = stack.pop() {
+            if set == 0 {
+                return false;
+            }
+            for label in 0..self.colours {
+                let next = self.follow(set, label);
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        true
+    }
+
+    /// Retourne `true` si la règle est pré-injective : deux configurations de la ligne infinie
+    /// qui ne diffèrent qu'en un nombre fini de cellules ont des images différentes.
+    ///
+    /// Deux telles configurations forment, dans le graphe des paires, un chemin qui quitte
+    /// la diagonale puis y revient. D'après le théorème du Jardin d'Éden (Moore et Myhill),
+    /// une règle est pré-injective si et seulement si elle est surjective.
+    pub fn is_pre_injective(&self) -> bool {
+        let pairs = self.pair_graph();
+        let diagonal = self.diagonal();
+        let mut seen = vec![false; pairs.len()];
+        let mut stack: Vec<usize> = (0..pairs.len())
+            .filter(|&pair| diagonal(pair))
+            .flat_map(|pair| pairs[pair].iter().copied())
+            .filter(|&pair| !diagonal(pair))
+            .collect();
+        for &pair in &stack {
+            seen[pair] = true;
+        }
+
+        while let Some(pair) = stack.pop() {
+            for &next in &pairs[pair] {
+                if diagonal(next) {
+                    return false;
+                }
+                if !seen[next] {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        true
+    }
+
+    /// Retourne `true` si la règle est injective sur la ligne infinie, c'est-à-dire réversible
+    /// (théorème de Curtis, Hedlund et Lyndon).
+    ///
+    /// Deux configurations distinctes de même image forment, dans le graphe des paires, un chemin
+    /// bi-infini qui passe hors de la diagonale : la règle est injective si aucun sommet hors
+    /// de la diagonale n'est à la fois atteint depuis un cycle et capable d'en atteindre un.
+    pub fn is_injective(&self) -> bool {
+        let pairs = self.pair_graph();
+        let mut predecessors = vec![vec![]; pairs.len()];
+        for (pair, successors) in pairs.iter().enumerate() {
+            for &next in successors {
+                predecessors[next].push(pair);
+            }
+        }
+
+        let (forward, backward) = (infinite_paths(&pairs), infinite_paths(&predecessors));
+        let diagonal = self.diagonal();
+        (0..pairs.len()).all(|pair| diagonal(pair) || !(forward[pair] && backward[pair]))
+    }
+
+    /// Retourne `true` si la règle est injective sur l'anneau de `len` cellules ; sur un ensemble
+    /// fini, c'est aussi dire qu'elle y est surjective.
+    ///
+    /// Deux anneaux distincts de même image forment un cycle de longueur `len` du graphe
+    /// des paires qui passe hors de la diagonale.
+    ///
+    /// # Panics
+    /// Si `len` est nul.
+    pub fn is_injective_on_ring(&self, len: usize) -> bool {
+        assert!(len > 0, "DeBruijnGraph: a ring has at least one cell");
+        let pairs = self.pair_graph();
+        let diagonal = self.diagonal();
+        (0..pairs.len()).filter(|&pair| !diagonal(pair)).all(|start| {
+            let mut reached = vec![false; pairs.len()];
+            reached[start] = true;
+            for _ in 0..len {
+                let mut next = vec![false; pairs.len()];
+                for pair in (0..pairs.len()).filter(|&pair| reached[pair]) {
+                    for &target in &pairs[pair] {
+                        next[target] = true;
+                    }
+                }
+                reached = next;
+            }
+            !reached[start]
+        })
+    }
+
+    /// Retourne `true` si la règle est injective sur les anneaux de toutes les longueurs :
+    /// aucun cycle du graphe des paires ne passe hors de la diagonale.
+    pub fn is_injective_on_rings(&self) -> bool {
+        let pairs = self.pair_graph();
+        let diagonal = self.diagonal();
+        (0..pairs.len()).filter(|&pair| !diagonal(pair)).all(|start| {
+            let mut seen = vec![false; pairs.len()];
+            let mut stack = pairs[start].clone();
+            while let Some(pair) = stack.pop() {
+                if pair == start {
+                    return false;
+                }
+                if !seen[pair] {
+                    seen[pair] = true;
+                    stack.extend(&pairs[pair]);
+                }
+            }
+            true
+        })
+    }
+
+    /// Retourne le graphe des paires : le sommet `u * n + v` représente deux chemins parallèles
+    /// du graphe de de Bruijn, en `u` et en `v`, et ses arêtes suivent deux arêtes de même étiquette.
+    fn pair_graph(&self) -> Vec<Vec<usize>> {
+        let n = self.node_count();
+        (0..n * n)
+            .map(|pair| {
+                let (u, v) = (pair / n, pair % n);
+                self.edges(u)
+                    .flat_map(|(_, next_u, label)| self.successors(v, label).map(move |next_v| next_u * n + next_v))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Retourne le test d'appartenance d'un sommet du graphe des paires à la diagonale.
+    fn diagonal(&self) -> impl Fn(usize) -> bool {
+        let n = self.node_count();
+        move |pair| pair / n == pair % n
+    }
+}
+
+/// Retourne, pour chaque sommet, `true` si un chemin infini en part, c'est-à-dire s'il atteint
+/// un cycle : les sommets sans successeur sont retirés jusqu'à stabilité.
+fn infinite_paths(graph: &[Vec<usize>]) -> Vec<bool> {
+    let mut alive = vec![true; graph.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for node in 0..graph.len() {
+            if alive[node] && !graph[node].iter().any(|&next| alive[next]) {
+                alive[node] = false;
+                changed = true;
+            }
+        }
+    }
+    alive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::row::Row;
+    use crate::rules::WolframRule;
+
+    #[test]
+    fn test_elementary_graph() {
+        let graph = DeBruijnGraph::new(&WolframRule::new(110)).unwrap();
+        assert_eq!(graph.node_count(), 4);
+        // Depuis le mot 01, le voisinage 010 donne 1 et mène à 10 ; le voisinage 011 donne 1 et mène à 11.
+        assert_eq!(graph.edges(0b01).collect::<Vec<_>>(), vec![(0, 0b10, 1), (1, 0b11, 1)]);
+        assert_eq!(graph.successors(0b11, 0).collect::<Vec<_>>(), vec![0b11]);
+        assert_eq!(graph.states(0b10), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_elementary_classification() {
+        let surjective = [
+            15, 30, 45, 51, 60, 75, 85, 86, 89, 90, 101, 102, 105, 106, 120,
+            135, 149, 150, 153, 154, 165, 166, 169, 170, 180, 195, 204, 210, 225, 240,
+        ];
+        let injective = [15, 51, 85, 170, 204, 240];
+
+        for rule_number in 0..=255u8 {
+            let graph = DeBruijnGraph::new(&WolframRule::new(rule_number)).unwrap();
+            assert_eq!(graph.is_surjective(), surjective.contains(&rule_number), "rule {}", rule_number);
+            assert_eq!(graph.is_pre_injective(), graph.is_surjective(), "rule {}", rule_number);
+            assert_eq!(graph.is_injective(), injective.contains(&rule_number), "rule {}", rule_number);
+            assert_eq!(graph.is_injective_on_rings(), injective.contains(&rule_number), "rule {}", rule_number);
+
+            let rule = WolframRule::new(rule_number);
+            for len in 1..=8 {
+                let images: HashSet<String> = (0..1usize << len)
+                    .map(|index| {
+                        let row = Row::new((0..len).map(|i| Cell::new(((index >> i) & 1) as u8)).collect());
+                        row.next(&rule).to_string()
+                    })
+                    .collect();
+                assert_eq!(graph.is_injective_on_ring(len), images.len() == 1 << len, "rule {} on {} cells", rule_number, len);
+            }
+        }
+
+        // La règle 150 est injective sur les anneaux dont la longueur n'est pas multiple de 3.
+        let graph = DeBruijnGraph::new(&WolframRule::new(150)).unwrap();
+        for len in 1..=30 {
+            assert_eq!(graph.is_injective_on_ring(len), len % 3 != 0);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::life::{LifeRule, ParseRuleError, Rule2D};
+use crate::palette::{Palette, DEFAULT_SYMBOLS};
+
+/// Règle « Generations » : une règle Life-like dont les cellules mourantes passent
+/// par des états réfractaires avant de redevenir mortes.
+///
+/// L'état 0 est mort, l'état 1 vivant et les états `2` à `C - 1` réfractaires. Une cellule morte
+/// naît selon `B`, une cellule vivante survit selon `S` et sinon devient réfractaire ; une cellule
+/// réfractaire avance d'un état à chaque itération, jusqu'à redevenir morte. Seules les voisines
+/// vivantes sont comptées. Avec `C = 2`, la règle est une règle Life-like ordinaire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GenerationsRule {
+    life: LifeRule,
+    colours: u8,
+}
+
+impl GenerationsRule {
+    /// Crée une règle Generations à `colours` états (`C`).
+    ///
+    /// # Exemple
+    /// ```text
+    /// let brain = GenerationsRule::new(&[2], &[], 3); // B2/S/C3, le cerveau de Brian
+    /// ```
+    pub fn new(birth: &[u8], survival: &[u8], colours: u8) -> GenerationsRule {
+        assert!(colours >= 2, "GenerationsRule: at least two colours are needed");
+        Self { life: LifeRule::new(birth, survival), colours }
+    }
+
+    /// Le cerveau de Brian, `B2/S/C3`.
+    pub fn brians_brain() -> GenerationsRule {
+        Self::new(&[2], &[], 3)
+    }
+
+    /// Star Wars, `B2/S345/C4`.
+    pub fn star_wars() -> GenerationsRule {
+        Self::new(&[2], &[3, 4, 5], 4)
+    }
+
+    /// Modèle de Greenberg-Hastings à `colours` états : une cellule au repos (0) est excitée (1)
+    /// si au moins `threshold` voisines le sont ; une cellule excitée parcourt ensuite
+    /// les états réfractaires `2` à `colours - 1` avant de revenir au repos.
+    pub fn greenberg_hastings(colours: u8, threshold: u8) -> GenerationsRule {
+        assert!(colours >= 3, "GenerationsRule: Greenberg-Hastings needs a refractory state");
+        let birth: Vec<u8> = (threshold.max(1)..=8).collect();
+        Self::new(&birth, &[], colours)
+    }
+
+    /// Palette par défaut : fond noir, cellules vivantes blanches, états réfractaires
+    /// du rouge vers le bleu sombre.
+    pub fn palette(&self) -> Palette {
+        let mut colours = vec![(0, 0, 0), (255, 255, 255)];
+        colours.extend(gradient((230, 60, 30), (30, 30, 120), self.colours as usize - 2));
+        Palette::new(symbols(self.colours)).with_colours(colours)
+    }
+}
+
+impl Rule2D for GenerationsRule {
+    fn apply(&self, state: u8, neighbours: &[u8]) -> u8 {
+        match state {
+            0 | 1 if self.life.apply(state, neighbours) == 1 => 1,
+            0 => 0,
+            _ => (state + 1) % self.colours,
+        }
+    }
+
+    fn colours(&self) -> u8 {
+        self.colours
+    }
+}
+
+impl FromStr for GenerationsRule {
+    type Err = ParseRuleError;
+
+    /// Lit une règle en notation `B2/S345/C4` (sans tenir compte de la casse)
+    /// ou en notation historique `S/B/C` sans lettres (`345/2/4`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (life, colours) = s.trim().rsplit_once('/').ok_or(ParseRuleError)?;
+        let colours = colours.strip_prefix(['C', 'c']).unwrap_or(colours);
+        let colours: u8 = colours.parse().map_err(|_| ParseRuleError)?;
+        if colours < 2 {
+            return Err(ParseRuleError);
+        }
+
+        Ok(Self { life: life.parse()?, colours })
+    }
+}
+
+impl fmt::Display for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/C{}", self.life, self.colours)
+    }
+}
+
+/// Automate cellulaire cyclique à `k` états : une cellule dans l'état `s` passe à l'état
+/// `s + 1 (mod k)` si au moins `threshold` de ses voisines sont déjà dans cet état.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CyclicRule {
+    colours: u8,
+    threshold: u8,
+}
+
+impl CyclicRule {
+    /// Crée un automate cyclique à `colours` états.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let rule = CyclicRule::new(14, 1); // l'automate cyclique de Griffeath
+    /// ```
+    pub fn new(colours: u8, threshold: u8) -> CyclicRule {
+        assert!(colours >= 2, "CyclicRule: at least two colours are needed");
+        Self { colours, threshold }
+    }
+
+    /// Palette par défaut : une couleur par état, réparties sur le cercle chromatique.
+    pub fn palette(&self) -> Palette {
+        let k = self.colours as usize;
+        let colours = (0..k).map(|s| hue(s as f64 / k as f64)).collect();
+        Palette::new(symbols(self.colours)).with_colours(colours)
+    }
+}
+
+impl Rule2D for CyclicRule {
+    fn apply(&self, state: u8, neighbours: &[u8]) -> u8 {
+        let successor = (state + 1) % self.colours;
+        let count = neighbours.iter().filter(|&&s| s == successor).count();
+        if count >= self.threshold as usize { successor } else { state }
+    }
+
+    fn colours(&self) -> u8 {
+        self.colours
+    }
+}
+
+/// Retourne `count` couleurs allant linéairement de `from` à `to`.
+fn gradient(from: (u8, u8, u8), to: (u8, u8, u8), count: usize) -> Vec<(u8, u8, u8)> {
+    let mix = |a: u8, b: u8, t: f64| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (0..count)
+        .map(|i| {
+            let t = if count > 1 { i as f64 / (count - 1) as f64 } else { 0.0 };
+            (mix(from.0, to.0, t), mix(from.1, to.1, t), mix(from.2, to.2, t))
+        })
+        .collect()
+}
+
+/// Retourne la couleur saturée de teinte `h` (un tour complet pour `h` de 0 à 1).
+fn hue(h: f64) -> (u8, u8, u8) {
+    let channel = |offset: f64| {
+        let x = ((h + offset).fract() * 6.0 - 3.0).abs() - 1.0;
+        (x.clamp(0.0, 1.0) * 255.0).round() as u8
+    };
+    (channel(0.0), channel(2.0 / 3.0), channel(1.0 / 3.0))
+}
+
+/// Symboles des états d'une règle à `colours` états, complétés par des lettres
+/// au-delà des symboles par défaut.
+pub fn symbols(colours: u8) -> Vec<char> {
+    DEFAULT_SYMBOLS
+        .iter()
+        .copied()
+        .chain(('A'..='Z').chain('a'..='z'))
+        .take(colours as usize)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton_2d::{Automaton2D, Edges, Neighbourhood};
+
+    #[test]
+    fn test_notation() {
+        assert_eq!("B2/S/C3".parse(), Ok(GenerationsRule::brians_brain()));
+        assert_eq!("345/2/4".parse(), Ok(GenerationsRule::star_wars()));
+        assert_eq!(GenerationsRule::star_wars().to_string(), "B2/S345/C4");
+        assert_eq!("B2/S/C1".parse::<GenerationsRule>(), Err(ParseRuleError));
+        assert_eq!("B2/S".parse::<GenerationsRule>(), Err(ParseRuleError));
+    }
+
+    #[test]
+    fn test_brians_brain() {
+        let mut automaton = Automaton2D::from_lines(&["......", "..##..", "......"], Box::new(GenerationsRule::brians_brain())).unwrap();
+        automaton.set_edges(Edges::Bounded).next();
+        assert_eq!(automaton.to_string(), "..##..\n..++..\n..##..\n");
+        automaton.next();
+        assert_eq!(automaton.to_string(), "..++..\n.#..#.\n..++..\n");
+    }
+
+    #[test]
+    fn test_greenberg_hastings_wave() {
+        let rule = GenerationsRule::greenberg_hastings(4, 1);
+        let mut automaton = Automaton2D::from_lines(&[".......", "...#...", "......."], Box::new(rule)).unwrap();
+        automaton.set_edges(Edges::Bounded).set_neighbourhood(Neighbourhood::VonNeumann);
+        automaton.next();
+        assert_eq!(automaton.to_string(), "...#...\n..#+#..\n...#...\n");
+        automaton.next();
+        assert_eq!(automaton.to_string(), "..#+#..\n.#+*+#.\n..#+#..\n");
+        automaton.next();
+        assert_eq!(automaton.to_string(), ".#+*+#.\n#+*.*+#\n.#+*+#.\n");
+    }
+
+    #[test]
+    fn test_cyclic() {
+        let rule = CyclicRule::new(3, 1);
+        assert_eq!(rule.apply(0, &[1, 0, 0, 0]), 1);
+        assert_eq!(rule.apply(2, &[1, 1, 2, 1]), 2);
+        assert_eq!(rule.apply(2, &[0, 1, 1, 1]), 0);
+        assert_eq!(CyclicRule::new(3, 2).apply(2, &[0, 1, 1, 1]), 2);
+
+        let mut automaton = Automaton2D::from_lines(&["..+#", "#.+.", "+#..", "..##"], Box::new(CyclicRule::new(3, 1))).unwrap();
+        automaton.set_neighbourhood(Neighbourhood::VonNeumann).evolve(20);
+        assert!(automaton.cells().iter().all(|cell| cell.state() < 3));
+    }
+
+    #[test]
+    fn test_palettes() {
+        let palette = GenerationsRule::star_wars().palette();
+        assert_eq!(palette.colour(0), Some((0, 0, 0)));
+        assert_eq!(palette.colour(1), Some((255, 255, 255)));
+        assert_eq!(palette.colour(2), Some((230, 60, 30)));
+        assert_eq!(palette.colour(3), Some((30, 30, 120)));
+        assert_eq!(palette.colour(4), None);
+
+        let palette = CyclicRule::new(3, 1).palette();
+        assert_eq!(palette.colour(0), Some((255, 0, 0)));
+        assert_eq!(palette.colour(1), Some((0, 255, 0)));
+        assert_eq!(palette.colour(2), Some((0, 0, 255)));
+        assert_eq!(symbols(12)[10..], ['A', 'B']);
+    }
+}
@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+use crate::cell::Cell;
+use crate::row::Row;
+use crate::rules::{elementary_number, Rule};
+
+/// Nœud de l'arbre : un segment de `2^level` cellules, formé de deux moitiés.
+///
+/// Les nœuds `0` et `1` sont les feuilles, une cellule dans l'état correspondant.
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    left: u32,
+    right: u32,
+    level: u8,
+}
+
+/// Moteur d'évolution mémoïsé, à la manière de HashLife, pour les règles binaires de rayon 1.
+///
+/// Une ligne est un arbre binaire de segments dont les nœuds identiques sont partagés
+/// (hash-consing). Pour chaque segment de `2^k` cellules, le moteur retient sa moitié centrale
+/// `2^j` générations plus tard (`j <= k - 2`), calculée une seule fois : une ligne répétitive
+/// (fond de la règle 110, règles 90 ou 184...) saute alors exponentiellement loin en peu d'opérations.
+///
+/// Le gain dépend de la régularité de l'évolution : le nombre de nœuds est borné par le nombre
+/// de segments distincts rencontrés. Sur un anneau dont l'orbite a un long cycle (la règle 90
+/// sur 37 cellules, par exemple), les segments intermédiaires sont presque tous distincts,
+/// et un long saut coûte alors plus de temps et de mémoire qu'une évolution directe.
+/// Les segments et les résultats sont gardés d'un appel à l'autre, sans limite par défaut
+/// (voir [`HashLife::set_max_nodes`] et [`HashLife::clear`]).
+///
+/// Les lignes retournées sont celles que donnerait [`Automaton::evolve`](crate::automaton::Automaton::evolve).
+///
+/// # Exemple
+/// ```text
+/// let mut engine = HashLife::new(&WolframRule::new(90)).unwrap();
+/// let row = engine.evolve_light_cone(&Row::new(vec![Cell::new(1)]), 1 << 20);
+/// ```
+pub struct HashLife {
+    rule_number: u8,
+    nodes: Vec<Node>,
+    /// Identifiant du nœud formé de deux moitiés données.
+    index: HashMap<(u32, u32), u32>,
+    /// Moitié centrale d'un nœud après `2^j` générations, indexée par `(nœud, j)`.
+    results: HashMap<(u32, u8), u32>,
+    /// Segments uniformes de chaque état, indexés par leur niveau.
+    uniform: [Vec<u32>; 2],
+    /// Nombre de segments au-delà duquel la mémoire est vidée.
+    max_nodes: Option<usize>,
+}
+
+impl HashLife {
+    /// Crée un moteur pour une règle, ou retourne `None` si la règle n'est pas binaire,
+    /// déterministe et de rayon 1.
+    pub fn new(rule: &dyn Rule) -> Option<HashLife> {
+        if rule.left_reach() != 1 || rule.right_reach() != 1 {
+            return None;
+        }
+        let rule_number = elementary_number(rule)?;
+        let leaves = [0, 1].map(|state| Node { left: state, right: state, level: 0 });
+
+        Some(Self {
+            rule_number,
+            nodes: leaves.to_vec(),
+            index: HashMap::new(),
+            results: HashMap::new(),
+            uniform: [vec![0], vec![1]],
+            max_nodes: None,
+        })
+    }
+
+    /// Retourne le numéro de Wolfram de la règle.
+    pub fn rule_number(&self) -> u8 {
+        self.rule_number
+    }
+
+    /// Retourne le nombre de segments distincts en mémoire.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Oublie les segments et les résultats mémorisés.
+    pub fn clear(&mut self) {
+        self.nodes.truncate(2);
+        self.index.clear();
+        self.results.clear();
+        self.uniform = [vec![0], vec![1]];
+    }
+
+    /// Limite la mémoire du moteur à environ `nodes` segments.
+    ///
+    /// Quand le nombre de segments dépasse la limite, les segments et les résultats mémorisés
+    /// sont oubliés comme par [`HashLife::clear`]. La limite est vérifiée avant chaque saut
+    /// d'une évolution sur un anneau, et au début de chaque appel à [`HashLife::evolve_light_cone`] :
+    /// un saut en cours peut la dépasser.
+    ///
+    /// # Exemple
+    /// ```text
+    /// engine.set_max_nodes(1 << 22);
+    /// for row in rows {
+    ///     engine.evolve(&row, 1 << 40);
+    /// }
+    /// ```
+    pub fn set_max_nodes(&mut self, nodes: usize) -> &mut Self {
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    /// Vide la mémoire si elle dépasse la limite fixée par [`HashLife::set_max_nodes`].
+    fn enforce_limit(&mut self) {
+        if self.max_nodes.is_some_and(|limit| self.nodes.len() > limit) {
+            self.clear();
+        }
+    }
+
+    /// Fait évoluer de `steps` générations une ligne refermée en anneau, comme un automate
+    /// de largeur fixe avec la condition aux bords par défaut ([`Boundary::Periodic`](crate::boundary::Boundary::Periodic)).
+    ///
+    /// L'anneau est déroulé en une ligne infinie de période `n` : ses segments de `2^k` cellules
+    /// ne commencent qu'à `n` positions distinctes modulo `n`, si bien que le nœud d'un saut de `2^j`
+    /// générations se construit en O(n j) opérations, quelle que soit la largeur de l'anneau.
+    /// Une évolution de `t` générations demande O(log t) sauts.
+    ///
+    /// # Panics
+    /// Si la ligne contient un état autre que 0 ou 1.
+    pub fn evolve(&mut self, row: &Row, steps: u64) -> Row {
+        let cells = binary_states(row);
+        let n = cells.len();
+        if n == 0 || steps == 0 {
+            return with_iteration(cells, row.iteration() + steps as usize);
+        }
+
+        // 2^l modulo n, pour chaque niveau l utile (au plus 2^65 cellules).
+        let mut powers = vec![1 % n];
+        while powers.len() <= 65 {
+            powers.push(2 * powers.last().unwrap() % n);
+        }
+        let mut cells = cells;
+        let mut remaining = steps;
+        while remaining > 0 {
+            self.enforce_limit();
+            let j = remaining.ilog2() as u8;
+            let level = (j + 2).max(n.next_power_of_two().trailing_zeros() as u8 + 1);
+            // Le nœud couvre les cellules [-2^(level - 2), 3 * 2^(level - 2)) de la ligne périodique.
+            let start = (n - powers[level as usize - 2]) % n;
+            let root = self.build_periodic(&cells, &powers, start, level, &mut HashMap::new());
+            let result = self.advance(root, j);
+            cells = self.cells(result, 0, n as u64);
+            remaining -= 1 << j;
+        }
+
+        with_iteration(cells, row.iteration() + steps as usize)
+    }
+
+    /// Fait évoluer de `steps` générations une ligne entourée d'un fond quiescent initialement à 0,
+    /// comme un automate créé par [`Automaton::new_light_cone`](crate::automaton::Automaton::new_light_cone).
+    ///
+    /// La ligne retournée s'étend de `steps` cellules de chaque côté de la ligne donnée.
+    ///
+    /// # Panics
+    /// Si la ligne contient un état autre que 0 ou 1.
+    pub fn evolve_light_cone(&mut self, row: &Row, steps: u64) -> Row {
+        self.enforce_limit();
+        let mut cells = binary_states(row);
+        let len = cells.len();
+        cells.resize(len.max(2).next_power_of_two(), 0);
+
+        // Le nœud `root` couvre les cellules [origin, origin + 2^level) ; les cellules hors du nœud
+        // sont dans l'état du fond. Les colonnes sont comptées depuis la première cellule de `row`.
+        let mut root = self.build(&cells);
+        let mut origin = 0i64;
+        let mut background = 0;
+        let (mut low, mut high) = (0i64, len as i64);
+
+        let mut remaining = steps;
+        while remaining > 0 {
+            let j = remaining.ilog2() as u8;
+            let reach = 1i64 << j;
+            (low, high) = (low - reach, high + reach);
+            // La moitié centrale du nœud, seule calculée, doit contenir les cellules voulues.
+            loop {
+                let level = self.nodes[root as usize].level;
+                let quarter = 1i64 << (level - 1) >> 1;
+                if level >= j + 2 && origin + quarter <= low && high <= origin + 3 * quarter {
+                    break;
+                }
+                let Node { left, right, level } = self.nodes[root as usize];
+                let padding = self.uniform(background, level - 1);
+                let (left, right) = (self.join(padding, left), self.join(right, padding));
+                root = self.join(left, right);
+                origin -= 1 << (level - 1);
+            }
+
+            let level = self.nodes[root as usize].level;
+            root = self.advance(root, j);
+            origin += 1 << (level - 2);
+            background = self.background_after(background, 1 << j);
+            remaining -= 1 << j;
+        }
+
+        let cells = self.cells(root, (low - origin) as u64, (high - origin) as u64);
+        with_iteration(cells, row.iteration() + steps as usize)
+    }
+
+    /// Retourne le nœud formé de deux moitiés de même niveau.
+    fn join(&mut self, left: u32, right: u32) -> u32 {
+        if let Some(&node) = self.index.get(&(left, right)) {
+            return node;
+        }
+        let level = self.nodes[left as usize].level + 1;
+        let node = self.nodes.len() as u32;
+        self.nodes.push(Node { left, right, level });
+        self.index.insert((left, right), node);
+        node
+    }
+
+    /// Retourne le segment de `2^level` cellules dans l'état donné.
+    fn uniform(&mut self, state: u8, level: u8) -> u32 {
+        while self.uniform[state as usize].len() <= level as usize {
+            let half = *self.uniform[state as usize].last().unwrap();
+            let node = self.join(half, half);
+            self.uniform[state as usize].push(node);
+        }
+        self.uniform[state as usize][level as usize]
+    }
+
+    /// Construit le nœud d'une suite d'états dont la longueur est une puissance de deux.
+    fn build(&mut self, states: &[u8]) -> u32 {
+        if states.len() == 1 {
+            return states[0] as u32;
+        }
+        let (left, right) = states.split_at(states.len() / 2);
+        let (left, right) = (self.build(left), self.build(right));
+        self.join(left, right)
+    }
+
+    /// Construit le nœud de niveau `level` de la ligne périodique de motif `states`,
+    /// qui commence à la cellule `start` du motif. `powers[l]` vaut `2^l` modulo la période.
+    fn build_periodic(&mut self, states: &[u8], powers: &[usize], start: usize, level: u8, built: &mut HashMap<(usize, u8), u32>) -> u32 {
+        if level == 0 {
+            return states[start] as u32;
+        }
+        if let Some(&node) = built.get(&(start, level)) {
+            return node;
+        }
+        let left = self.build_periodic(states, powers, start, level - 1, built);
+        let middle = (start + powers[level as usize - 1]) % states.len();
+        let right = self.build_periodic(states, powers, middle, level - 1, built);
+        let node = self.join(left, right);
+        built.insert((start, level), node);
+        node
+    }
+
+    /// Retourne les états des cellules `[from, to)` d'un nœud.
+    fn cells(&self, node: u32, from: u64, to: u64) -> Vec<u8> {
+        let mut states = Vec::with_capacity((to - from) as usize);
+        self.collect(node, from, to, &mut states);
+        states
+    }
+
+    fn collect(&self, node: u32, from: u64, to: u64, states: &mut Vec<u8>) {
+        let Node { left, right, level } = self.nodes[node as usize];
+        if from >= to {
+            return;
+        }
+        if level == 0 {
+            states.push(node as u8);
+            return;
+        }
+        let half = 1u64 << (level - 1);
+        if from < half {
+            self.collect(left, from, to.min(half), states);
+        }
+        if to > half {
+            self.collect(right, from.saturating_sub(half), to - half, states);
+        }
+    }
+
+    /// Retourne la moitié centrale d'un nœud.
+    fn centre(&mut self, node: u32) -> u32 {
+        let Node { left, right, .. } = self.nodes[node as usize];
+        let (inner_left, inner_right) = (self.nodes[left as usize].right, self.nodes[right as usize].left);
+        self.join(inner_left, inner_right)
+    }
+
+    /// Retourne la moitié centrale d'un nœud de niveau `k` après `2^j` générations, avec `j <= k - 2`.
+    ///
+    /// Le nœud `[a b c d]` est couvert par trois nœuds de niveau `k - 1` qui se chevauchent,
+    /// `[a b]`, `[b c]` et `[c d]`, dont les moitiés centrales `r0`, `r1` et `r2` sont avancées
+    /// de `2^(k - 3)` générations si le saut est complet, ou laissées telles quelles sinon.
+    /// Les nœuds `[r0 r1]` et `[r1 r2]` sont ensuite avancés des générations restantes.
+    fn advance(&mut self, node: u32, j: u8) -> u32 {
+        if let Some(&result) = self.results.get(&(node, j)) {
+            return result;
+        }
+        let Node { left, right, level } = self.nodes[node as usize];
+        debug_assert!(level >= 2 && j <= level - 2);
+
+        let result = if level == 2 {
+            let (a, b) = (self.nodes[left as usize].left, self.nodes[left as usize].right);
+            let (c, d) = (self.nodes[right as usize].left, self.nodes[right as usize].right);
+            let x = self.apply(a, b, c);
+            let y = self.apply(b, c, d);
+            self.join(x, y)
+        } else {
+            let middle = self.join(self.nodes[left as usize].right, self.nodes[right as usize].left);
+            let (r0, r1, r2) = if j == level - 2 {
+                (self.advance(left, level - 3), self.advance(middle, level - 3), self.advance(right, level - 3))
+            } else {
+                (self.centre(left), self.centre(middle), self.centre(right))
+            };
+            let j = j.min(level - 3);
+            let (first, second) = (self.join(r0, r1), self.join(r1, r2));
+            let (first, second) = (self.advance(first, j), self.advance(second, j));
+            self.join(first, second)
+        };
+
+        self.results.insert((node, j), result);
+        result
+    }
+
+    fn apply(&self, left: u32, centre: u32, right: u32) -> u32 {
+        ((self.rule_number >> ((left << 2) | (centre << 1) | right)) & 1) as u32
+    }
+
+    /// Retourne l'état du fond quiescent après `steps` générations.
+    fn background_after(&self, state: u8, steps: u64) -> u8 {
+        // Une application de {0, 1} dans lui-même est constante, l'identité ou l'échange :
+        // la suite des états est périodique de période 1 ou 2 dès la première génération.
+        let steps = if steps == 0 { 0 } else { 1 + (steps - 1) % 2 };
+        (0..steps).fold(state, |state, _| (self.rule_number >> (7 * state)) & 1)
+    }
+}
+
+fn binary_states(row: &Row) -> Vec<u8> {
+    (0..row.len())
+        .map(|i| {
+            let state = row.get(i).unwrap().state();
+            assert!(state < 2, "HashLife: only binary rows can be evolved");
+            state
+        })
+        .collect()
+}
+
+fn with_iteration(states: Vec<u8>, iteration: usize) -> Row {
+    let mut row = Row::new(states.into_iter().map(Cell::new).collect());
+    row.set_iteration(iteration);
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::Automaton;
+    use crate::orbit::Outcome;
+    use crate::random::Random;
+    use crate::retention::Retention;
+    use crate::rules::{Rule30, WolframRule};
+
+    fn random_row(len: usize, seed: u64) -> Row {
+        let mut random = Random::new(seed);
+        Row::new((0..len).map(|_| Cell::new(random.below(2) as u8)).collect())
+    }
+
+    #[test]
+    fn test_matches_automaton() {
+        for rule_number in [30, 90, 110, 184, 54, 1, 51, 255] {
+            let mut engine = HashLife::new(&WolframRule::new(rule_number)).unwrap();
+            for len in [1, 5, 16, 37, 64] {
+                let row = random_row(len, rule_number as u64 * 100 + len as u64);
+                for steps in [0, 1, 7, 100, 333] {
+                    let mut automaton = Automaton::new(row.clone(), Box::new(WolframRule::new(rule_number)));
+                    automaton.evolve(steps);
+                    let actual = engine.evolve(&row, steps);
+                    assert_eq!(actual.to_string(), automaton.current().to_string(), "rule {} on {} cells, {} steps", rule_number, len, steps);
+                    assert_eq!(actual.iteration(), steps as usize);
+
+                    let mut automaton = Automaton::new_light_cone(row.clone(), 0, Box::new(WolframRule::new(rule_number)));
+                    automaton.evolve(steps);
+                    let actual = engine.evolve_light_cone(&row, steps);
+                    assert_eq!(actual.to_string(), automaton.current().to_string(), "rule {} in light cone, {} steps", rule_number, steps);
+                }
+            }
+        }
+
+        assert_eq!(HashLife::new(&Rule30).unwrap().rule_number(), 30);
+        assert!(HashLife::new(&WolframRule::with_window(6, 2)).is_none());
+    }
+
+    #[test]
+    fn test_exponential_jumps() {
+        // La règle 170 décale la ligne d'une cellule vers la gauche à chaque génération.
+        let mut engine = HashLife::new(&WolframRule::new(170)).unwrap();
+        let row = random_row(64, 7);
+        let steps = 1_000_000_000_003u64;
+        let shift = (steps % 64) as usize;
+        let expected: String = row.to_string().chars().cycle().skip(shift).take(64).collect();
+        assert_eq!(engine.evolve(&row, steps).to_string(), expected);
+        // Les anneaux dont la largeur n'est pas une puissance de deux sautent aussi loin.
+        for len in [37, 100, 1000] {
+            let row = random_row(len, len as u64);
+            let shift = (steps % len as u64) as usize;
+            let expected: String = row.to_string().chars().cycle().skip(shift).take(len).collect();
+            assert_eq!(engine.evolve(&row, steps).to_string(), expected, "{} cells", len);
+        }
+
+        // Après 2^k générations de la règle 90, une graine isolée ne laisse que deux cellules vivantes.
+        let mut engine = HashLife::new(&WolframRule::new(90)).unwrap();
+        let steps = 1 << 16;
+        let row = engine.evolve_light_cone(&Row::new(vec![Cell::new(1)]), steps);
+        assert_eq!(row.len(), 2 * steps as usize + 1);
+        let alive: Vec<usize> = (0..row.len()).filter(|&i| row.get(i).unwrap().state() == 1).collect();
+        assert_eq!(alive, vec![0, 2 * steps as usize]);
+        assert!(engine.node_count() < 10_000);
+    }
+
+    #[test]
+    fn test_max_nodes() {
+        let mut unlimited = HashLife::new(&WolframRule::new(30)).unwrap();
+        let mut limited = HashLife::new(&WolframRule::new(30)).unwrap();
+        limited.set_max_nodes(2_000);
+        for (len, steps) in [(100, 1_000), (101, 777), (64, 5_000), (100, 3_000)] {
+            let row = random_row(len, steps);
+            let mut automaton = Automaton::new(row.clone(), Box::new(WolframRule::new(30)));
+            automaton.evolve(steps);
+            assert_eq!(limited.evolve(&row, steps).to_string(), automaton.current().to_string(), "{} cells", len);
+            unlimited.evolve(&row, steps);
+        }
+        assert!(limited.node_count() < unlimited.node_count());
+
+        // La limite s'applique aussi entre deux évolutions en cône de lumière.
+        let seed = Row::new(vec![Cell::new(1)]);
+        limited.evolve_light_cone(&seed, 500);
+        let mut automaton = Automaton::new_light_cone(seed.clone(), 0, Box::new(WolframRule::new(30)));
+        automaton.evolve(500);
+        assert_eq!(limited.evolve_light_cone(&seed, 500).to_string(), automaton.current().to_string());
+    }
+
+    #[test]
+    fn test_long_runs_on_any_ring() {
+        // La référence ramène l'orbite de l'anneau à son cycle, détecté par l'automate.
+        let steps = (1u64 << 40) + 12_345;
+        for (rule_number, len) in [(184, 37), (184, 250), (110, 30), (30, 11), (30, 13), (90, 3)] {
+            let row = random_row(len, len as u64 + rule_number as u64);
+            let mut automaton = Automaton::new(row.clone(), Box::new(WolframRule::new(rule_number)));
+            automaton.set_retention(Retention::Nothing);
+            let Outcome::Cycle { pre_period, period, .. } = automaton.evolve_until(1 << 20, |_, _| false) else { panic!("rule {}", rule_number) };
+            let mut reference = Automaton::new(row.clone(), Box::new(WolframRule::new(rule_number)));
+            reference.evolve(pre_period as u64 + (steps - pre_period as u64) % period as u64);
+
+            let mut engine = HashLife::new(&WolframRule::new(rule_number)).unwrap();
+            let actual = engine.evolve(&row, steps);
+            assert_eq!(actual.to_string(), reference.current().to_string(), "rule {} on {} cells", rule_number, len);
+            assert_eq!(actual.iteration(), steps as usize);
+        }
+    }
+}
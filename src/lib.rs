@@ -1,8 +1,32 @@
 pub mod cell;
+pub mod natural;
+pub mod palette;
 pub mod rules;
+pub mod rule_info;
+pub mod compiled;
+pub mod hashlife;
+pub mod linear;
+pub mod de_bruijn;
+pub mod preimage;
+pub mod state_graph;
+pub mod periodic;
+pub mod random;
+pub mod stochastic;
 pub mod row;
+pub mod bit_row;
+pub mod simd;
+mod parallel;
+pub mod boundary;
+pub mod update;
+pub mod retention;
+pub mod sink;
+pub mod orbit;
 pub mod automaton;
 pub mod automaton_analysis;
+pub mod damage;
+pub mod life;
+pub mod generations;
+pub mod automaton_2d;
 pub mod pattern;
 mod utils;
 pub mod fast_30;
@@ -0,0 +1,150 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Règle locale d'un automate cellulaire bidimensionnel.
+///
+/// Le nouvel état d'une cellule dépend de son état et des états de ses voisines ;
+/// l'ordre des voisines n'est pas spécifié, la règle doit donc être symétrique.
+pub trait Rule2D {
+    /// Calcule le nouvel état d'une cellule à partir de son état et de ceux de ses voisines.
+    fn apply(&self, state: u8, neighbours: &[u8]) -> u8;
+
+    /// Retourne le nombre d'états `k` d'une cellule (de `0` à `k - 1`).
+    fn colours(&self) -> u8 {
+        2
+    }
+}
+
+/// Erreur retournée quand une règle n'est pas écrite dans une notation reconnue.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRuleError;
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rule notation")
+    }
+}
+
+impl std::error::Error for ParseRuleError {}
+
+/// Règle binaire « Life-like » : une cellule morte naît si son nombre de voisines vivantes
+/// est dans l'ensemble `B`, une cellule vivante survit si ce nombre est dans l'ensemble `S`.
+///
+/// Seul l'état 1 compte comme vivant ; la règle se lit et s'écrit en notation B/S (`B3/S23`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LifeRule {
+    /// Bit `n` à 1 si une cellule morte entourée de `n` voisines vivantes naît.
+    birth: u16,
+    /// Bit `n` à 1 si une cellule vivante entourée de `n` voisines vivantes survit.
+    survival: u16,
+}
+
+impl LifeRule {
+    /// Crée une règle à partir des nombres de voisines provoquant une naissance et une survie.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let life = LifeRule::new(&[3], &[2, 3]); // B3/S23
+    /// ```
+    pub fn new(birth: &[u8], survival: &[u8]) -> LifeRule {
+        assert!(birth.iter().chain(survival).all(|&n| n <= 8), "LifeRule: a cell has at most 8 neighbours");
+        let mask = |counts: &[u8]| counts.iter().fold(0, |mask, &n| mask | (1 << n));
+        Self { birth: mask(birth), survival: mask(survival) }
+    }
+
+    /// Le Jeu de la vie de Conway, `B3/S23`.
+    pub fn life() -> LifeRule {
+        Self::new(&[3], &[2, 3])
+    }
+
+    /// HighLife, `B36/S23`, qui possède un réplicateur.
+    pub fn high_life() -> LifeRule {
+        Self::new(&[3, 6], &[2, 3])
+    }
+
+    /// Seeds, `B2/S` : aucune cellule ne survit.
+    pub fn seeds() -> LifeRule {
+        Self::new(&[2], &[])
+    }
+
+    /// Retourne `true` si une cellule morte entourée de `count` voisines vivantes naît.
+    pub fn is_birth(&self, count: usize) -> bool {
+        (self.birth >> count) & 1 == 1
+    }
+
+    /// Retourne `true` si une cellule vivante entourée de `count` voisines vivantes survit.
+    pub fn is_survival(&self, count: usize) -> bool {
+        (self.survival >> count) & 1 == 1
+    }
+}
+
+impl Rule2D for LifeRule {
+    fn apply(&self, state: u8, neighbours: &[u8]) -> u8 {
+        let count = neighbours.iter().filter(|&&s| s == 1).count();
+        let alive = if state == 1 { self.is_survival(count) } else { self.is_birth(count) };
+        alive as u8
+    }
+}
+
+/// Lit la liste des chiffres d'une partie de la notation B/S.
+pub(crate) fn parse_counts(digits: &str) -> Result<Vec<u8>, ParseRuleError> {
+    digits
+        .chars()
+        .map(|c| c.to_digit(10).filter(|&n| n <= 8).map(|n| n as u8).ok_or(ParseRuleError))
+        .collect()
+}
+
+/// Écrit la liste des nombres de voisines d'un masque, dans l'ordre croissant.
+pub(crate) fn format_counts(mask: u16) -> String {
+    (0..=8).filter(|n| (mask >> n) & 1 == 1).map(|n| n.to_string()).collect()
+}
+
+impl FromStr for LifeRule {
+    type Err = ParseRuleError;
+
+    /// Lit une règle en notation `B3/S23` (dans un ordre quelconque, sans tenir compte
+    /// de la casse) ou en notation historique `S/B` sans lettres (`23/3`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, second) = s.trim().split_once('/').ok_or(ParseRuleError)?;
+        let (birth, survival) = match (first.chars().next(), second.chars().next()) {
+            (Some('B' | 'b'), Some('S' | 's')) => (&first[1..], &second[1..]),
+            (Some('S' | 's'), Some('B' | 'b')) => (&second[1..], &first[1..]),
+            _ => (second, first),
+        };
+
+        Ok(Self::new(&parse_counts(birth)?, &parse_counts(survival)?))
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "B{}/S{}", format_counts(self.birth), format_counts(self.survival))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notation() {
+        assert_eq!("B3/S23".parse(), Ok(LifeRule::life()));
+        assert_eq!("s23/b3".parse(), Ok(LifeRule::life()));
+        assert_eq!("23/3".parse(), Ok(LifeRule::life()));
+        assert_eq!("B36/S23".parse(), Ok(LifeRule::high_life()));
+        assert_eq!("B2/S".parse(), Ok(LifeRule::seeds()));
+        assert_eq!(LifeRule::high_life().to_string(), "B36/S23");
+        assert_eq!(LifeRule::seeds().to_string(), "B2/S");
+        assert_eq!("B9/S23".parse::<LifeRule>(), Err(ParseRuleError));
+        assert_eq!("B3S23".parse::<LifeRule>(), Err(ParseRuleError));
+    }
+
+    #[test]
+    fn test_apply() {
+        let life = LifeRule::life();
+        assert_eq!(life.apply(0, &[1, 1, 1, 0, 0, 0, 0, 0]), 1);
+        assert_eq!(life.apply(1, &[1, 1, 0, 0, 0, 0, 0, 0]), 1);
+        assert_eq!(life.apply(1, &[1, 1, 1, 1, 0, 0, 0, 0]), 0);
+        assert_eq!(life.apply(0, &[1, 1, 0, 0, 0, 0, 0, 0]), 0);
+    }
+}
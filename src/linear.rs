@@ -0,0 +1,260 @@
+use crate::cell::Cell;
+use crate::row::Row;
+use crate::rule_info::RuleInfo;
+use crate::rules::Rule;
+
+/// Règle élémentaire additive (0, 60, 90, 102, 150, 170, 204, 240), évaluée sans simulation.
+///
+/// Une telle règle est linéaire sur GF(2) : avec `S` le décalage `(S x)[i] = x[i + 1]`,
+/// une génération applique l'opérateur `P = a S^-1 + b + c S`, où `a`, `b` et `c` indiquent
+/// si la voisine de gauche, la cellule et la voisine de droite comptent. La ligne au temps `t`
+/// est `P^t x`, et en caractéristique 2, `P^(2^k) = a S^-2^k + b + c S^2^k` (Frobenius) :
+/// il suffit d'un passage sur la ligne par bit de `t`.
+///
+/// Pour la règle 90, le coefficient de `S^(2j - t)` dans `P^t` est le coefficient binomial
+/// `C(t, j)` modulo 2, soit `1` si et seulement si `j & t == j` (théorème de Lucas).
+///
+/// # Exemple
+/// ```text
+/// let rule = LinearRule::new(90).unwrap();
+/// let row = rule.row(&first_row, 1_000_000_000_000);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinearRule {
+    rule_number: u8,
+    left: bool,
+    centre: bool,
+    right: bool,
+}
+
+impl LinearRule {
+    /// Crée la règle élémentaire `rule_number`, ou retourne `None` si elle n'est pas additive.
+    pub fn new(rule_number: u8) -> Option<LinearRule> {
+        if !RuleInfo::elementary(rule_number).is_additive() {
+            return None;
+        }
+        let bit = |index: u8| (rule_number >> index) & 1 == 1;
+
+        Some(Self { rule_number, left: bit(4), centre: bit(2), right: bit(1) })
+    }
+
+    /// Retourne le numéro de Wolfram de la règle.
+    pub fn rule_number(&self) -> u8 {
+        self.rule_number
+    }
+
+    /// Retourne le coefficient de `S^offset` dans `P^steps` sur une ligne infinie : l'état
+    /// de la cellule `x` au temps `steps` est la somme modulo 2 des cellules initiales
+    /// `x + offset` dont le coefficient vaut 1.
+    ///
+    /// Le coefficient compte, modulo 2, les façons d'écrire `offset` comme une somme
+    /// de termes `e_k 2^k` sur les bits `k` de `steps`, avec `e_k` parmi les décalages
+    /// de la règle (-1, 0 ou 1). Le calcul parcourt ces bits un à un, en O(log t).
+    pub fn coefficient(&self, steps: u64, offset: i64) -> u8 {
+        let shifts: Vec<i128> = [(self.left, -1), (self.centre, 0), (self.right, 1)]
+            .into_iter()
+            .filter_map(|(used, shift)| used.then_some(shift))
+            .collect();
+
+        // Restes à écrire avec les bits suivants, et parité du nombre de façons d'y arriver.
+        let mut remainders: Vec<(i128, u8)> = vec![(offset as i128, 1)];
+        for bit in 0..u64::BITS - steps.leading_zeros() {
+            let choices: &[i128] = if (steps >> bit) & 1 == 1 { &shifts } else { &[0] };
+            let mut next: Vec<(i128, u8)> = vec![];
+            for &(remainder, parity) in &remainders {
+                for &shift in choices.iter().filter(|&&shift| (remainder - shift) % 2 == 0) {
+                    let remainder = (remainder - shift) / 2;
+                    match next.iter_mut().find(|(r, _)| *r == remainder) {
+                        Some((_, p)) => *p ^= parity,
+                        None => next.push((remainder, parity)),
+                    }
+                }
+            }
+            next.retain(|&(_, parity)| parity == 1);
+            remainders = next;
+        }
+
+        remainders.iter().filter(|&&(remainder, _)| remainder == 0).fold(0, |sum, &(_, parity)| sum ^ parity)
+    }
+
+    /// Retourne l'état au temps `steps` de la cellule `column` d'une ligne refermée en anneau,
+    /// comme avec la condition aux bords par défaut d'un automate.
+    ///
+    /// Les coefficients de `P^steps` modulo `S^n - 1` sont calculés en O(n log t).
+    pub fn cell(&self, row: &Row, steps: u64, column: usize) -> u8 {
+        let states = binary_states(row);
+        let n = states.len();
+        assert!(column < n, "LinearRule: column out of the row");
+
+        let mut identity = vec![0; n];
+        identity[0] = 1;
+        let coefficients = self.power_on_ring(identity, steps, true);
+        coefficients
+            .iter()
+            .enumerate()
+            .fold(0, |sum, (offset, &coefficient)| sum ^ (coefficient & states[(column + offset) % n]))
+    }
+
+    /// Retourne la ligne au temps `steps` d'une ligne refermée en anneau, comme
+    /// [`Automaton::evolve`](crate::automaton::Automaton::evolve) avec la condition aux bords
+    /// par défaut, en O(n log t).
+    pub fn row(&self, row: &Row, steps: u64) -> Row {
+        let states = binary_states(row);
+        let states = self.power_on_ring(states, steps, false);
+        with_iteration(states, row.iteration() + steps as usize)
+    }
+
+    /// Retourne l'état au temps `steps` de la cellule `column` d'une ligne entourée de cellules à 0.
+    ///
+    /// La colonne 0 est la première cellule de `row` ; la cellule peut se trouver hors de la ligne,
+    /// dans le cône de lumière. Le calcul prend O(n log t) pour une ligne de `n` cellules.
+    pub fn cell_light_cone(&self, row: &Row, steps: u64, column: i64) -> u8 {
+        binary_states(row)
+            .iter()
+            .enumerate()
+            .filter(|&(_, &state)| state == 1)
+            .fold(0, |sum, (i, _)| sum ^ self.coefficient(steps, i as i64 - column))
+    }
+
+    /// Retourne la ligne au temps `steps` d'une ligne entourée de cellules à 0, étendue de `steps`
+    /// cellules de chaque côté, comme un automate créé par
+    /// [`Automaton::new_light_cone`](crate::automaton::Automaton::new_light_cone).
+    pub fn row_light_cone(&self, row: &Row, steps: u64) -> Row {
+        let mut states = binary_states(row);
+        for bit in 0..u64::BITS - steps.leading_zeros() {
+            let shift = 1usize << bit;
+            if (steps >> bit) & 1 == 0 {
+                continue;
+            }
+            // La ligne s'étend de `shift` cellules de chaque côté.
+            let old = |i: usize| if i < states.len() { states[i] } else { 0 };
+            states = (0..states.len() + 2 * shift)
+                .map(|i| {
+                    let left = self.left && i >= 2 * shift && old(i - 2 * shift) == 1;
+                    let centre = self.centre && i >= shift && old(i - shift) == 1;
+                    let right = self.right && old(i) == 1;
+                    (left ^ centre ^ right) as u8
+                })
+                .collect();
+        }
+        with_iteration(states, row.iteration() + steps as usize)
+    }
+
+    /// Applique `P^steps` aux états d'un anneau, bit par bit de `steps`.
+    ///
+    /// Pour une ligne, la cellule `i` lit la voisine de droite en `i + 2^k` ; pour les coefficients
+    /// d'un polynôme (`mirrored`), le terme `c S^2^k` déplace au contraire le coefficient de `d` en `d + 2^k`.
+    fn power_on_ring(&self, mut states: Vec<u8>, steps: u64, mirrored: bool) -> Vec<u8> {
+        let n = states.len();
+        if n == 0 {
+            return states;
+        }
+        for bit in 0..u64::BITS - steps.leading_zeros() {
+            if (steps >> bit) & 1 == 0 {
+                continue;
+            }
+            let shift = ((1u128 << bit) % n as u128) as usize;
+            let towards_right = if mirrored { (n - shift) % n } else { shift };
+            let towards_left = (n - towards_right) % n;
+            states = (0..n)
+                .map(|i| {
+                    let right = self.right && states[(i + towards_right) % n] == 1;
+                    let centre = self.centre && states[i] == 1;
+                    let left = self.left && states[(i + towards_left) % n] == 1;
+                    (left ^ centre ^ right) as u8
+                })
+                .collect();
+        }
+        states
+    }
+}
+
+impl Rule for LinearRule {
+    fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        let [left, centre, right] = [0, 1, 2].map(|i| neighbourhood[i] == 1);
+        ((self.left && left) ^ (self.centre && centre) ^ (self.right && right)) as u8
+    }
+
+    fn lookup_table(&self) -> Option<Vec<u8>> {
+        Some((0..8).map(|index| (self.rule_number >> index) & 1).collect())
+    }
+}
+
+fn binary_states(row: &Row) -> Vec<u8> {
+    (0..row.len())
+        .map(|i| {
+            let state = row.get(i).unwrap().state();
+            assert!(state < 2, "LinearRule: only binary rows can be evaluated");
+            state
+        })
+        .collect()
+}
+
+fn with_iteration(states: Vec<u8>, iteration: usize) -> Row {
+    let mut row = Row::new(states.into_iter().map(Cell::new).collect());
+    row.set_iteration(iteration);
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::Automaton;
+    use crate::random::Random;
+    use crate::rules::WolframRule;
+
+    const ADDITIVE: [u8; 8] = [0, 60, 90, 102, 150, 170, 204, 240];
+
+    fn random_row(len: usize, seed: u64) -> Row {
+        let mut random = Random::new(seed);
+        Row::new((0..len).map(|_| Cell::new(random.below(2) as u8)).collect())
+    }
+
+    #[test]
+    fn test_matches_wolfram_rule() {
+        assert!(LinearRule::new(30).is_none());
+        for rule_number in ADDITIVE {
+            let rule = LinearRule::new(rule_number).unwrap();
+            for len in [1, 2, 7, 32, 45] {
+                let row = random_row(len, rule_number as u64 + len as u64);
+                let mut ring = Automaton::new(row.clone(), Box::new(WolframRule::new(rule_number)));
+                let mut cone = Automaton::new_light_cone(row.clone(), 0, Box::new(WolframRule::new(rule_number)));
+                for steps in 0..=70u64 {
+                    let expected = ring.current();
+                    assert_eq!(rule.row(&row, steps).to_string(), expected.to_string(), "rule {} on {} cells at time {}", rule_number, len, steps);
+                    for column in 0..len {
+                        assert_eq!(rule.cell(&row, steps, column), expected.get(column).unwrap().state());
+                    }
+
+                    let expected = cone.current();
+                    let actual = rule.row_light_cone(&row, steps);
+                    assert_eq!(actual.to_string(), expected.to_string(), "rule {} in light cone at time {}", rule_number, steps);
+                    assert_eq!(actual.iteration(), steps as usize);
+                    for (i, column) in (-(steps as i64)..(len as i64 + steps as i64)).enumerate().step_by(3) {
+                        assert_eq!(rule.cell_light_cone(&row, steps, column), expected.get(i).unwrap().state());
+                    }
+
+                    ring.next();
+                    cone.next();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lucas() {
+        let rule = LinearRule::new(90).unwrap();
+        let steps = (1u64 << 40) + 0b1011_0110;
+        for j in [0, 1, 2, 4, 6, 0b1011_0110, 0b1000_0010, 1 << 40, (1 << 40) + 3, (1 << 40) + 0b110] {
+            let expected = (j & steps == j) as u8;
+            assert_eq!(rule.coefficient(steps, 2 * j as i64 - steps as i64), expected, "C({}, {})", steps, j);
+        }
+        // Les termes de P^t sont de même parité que t.
+        assert_eq!(rule.coefficient(steps, 1), 0);
+
+        // Sur un anneau de 2^p cellules, la règle 90 éteint toute ligne en 2^(p - 1) générations.
+        let row = random_row(64, 3);
+        assert!(rule.row(&row, 1_000_000_000_032).to_string().chars().all(|c| c == '.'));
+        assert_eq!(LinearRule::new(170).unwrap().row(&row, 1 << 50).to_string(), row.to_string());
+    }
+}
@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul};
+use std::str::FromStr;
+
+const LIMB_BITS: u32 = 32;
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+/// Entier naturel en précision arbitraire.
+///
+/// Sert principalement aux numéros de Wolfram des règles à `k` états, qui dépassent
+/// `u64` dès `k = 4` en rayon 1 (il y a `k^(k^3)` règles). Les chiffres sont stockés
+/// en base `2^32`, du poids faible au poids fort, sans zéro de tête.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Natural {
+    limbs: Vec<u32>,
+}
+
+/// Erreur retournée quand une chaîne n'est pas un entier naturel décimal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseNaturalError;
+
+impl fmt::Display for ParseNaturalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal natural number")
+    }
+}
+
+impl std::error::Error for ParseNaturalError {}
+
+impl Natural {
+    /// Retourne l'entier 0.
+    pub fn zero() -> Natural {
+        Self { limbs: vec![] }
+    }
+
+    /// Retourne l'entier 1.
+    pub fn one() -> Natural {
+        Self::from(1u64)
+    }
+
+    /// Retourne `true` si l'entier vaut 0.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Reconstruit un entier à partir de ses chiffres en base `base`,
+    /// donnés du poids faible au poids fort.
+    ///
+    /// # Exemple
+    /// ```text
+    /// assert_eq!(Natural::from_digits(&[0, 1, 2], 3), Natural::from(21u64)); // 0 + 1*3 + 2*9
+    /// ```
+    pub fn from_digits(digits: &[u8], base: u8) -> Natural {
+        let mut result = Self::zero();
+        for &digit in digits.iter().rev() {
+            result.mul_small(base as u32);
+            result.add_small(digit as u32);
+        }
+        result
+    }
+
+    /// Retourne les `count` premiers chiffres de l'entier en base `base`, du poids faible
+    /// au poids fort, ou `None` si l'entier ne tient pas sur `count` chiffres.
+    pub fn digits(&self, base: u8, count: usize) -> Option<Vec<u8>> {
+        let mut rest = self.clone();
+        let mut digits = Vec::with_capacity(count);
+        for _ in 0..count {
+            digits.push(rest.div_small(base as u32) as u8);
+        }
+
+        if rest.is_zero() { Some(digits) } else { None }
+    }
+
+    /// Retourne les 64 bits de poids faible de l'entier.
+    pub fn low_u64(&self) -> u64 {
+        let low = self.limbs.first().copied().unwrap_or(0) as u64;
+        let high = self.limbs.get(1).copied().unwrap_or(0) as u64;
+        low | (high << LIMB_BITS)
+    }
+
+    /// Convertit l'entier en `u128`, s'il y tient.
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(self.limbs.iter().rev().fold(0u128, |acc, &limb| (acc << LIMB_BITS) | limb as u128))
+    }
+
+    /// Multiplie l'entier par un petit facteur.
+    pub fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0u64;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> LIMB_BITS;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        self.normalize();
+    }
+
+    /// Ajoute un petit terme à l'entier.
+    pub fn add_small(&mut self, term: u32) {
+        let mut carry = term as u64;
+        for limb in self.limbs.iter_mut() {
+            if carry == 0 {
+                break;
+            }
+            let sum = *limb as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> LIMB_BITS;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// Divise l'entier par un petit diviseur non nul et retourne le reste.
+    pub fn div_small(&mut self, divisor: u32) -> u32 {
+        assert!(divisor > 0, "Natural::div_small: division by zero");
+        let mut remainder = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let current = (remainder << LIMB_BITS) | *limb as u64;
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        self.normalize();
+        remainder as u32
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u64> for Natural {
+    fn from(value: u64) -> Self {
+        Self::from(value as u128)
+    }
+}
+
+impl From<u128> for Natural {
+    fn from(value: u128) -> Self {
+        let mut limbs: Vec<u32> = (0..4).map(|i| (value >> (i * LIMB_BITS)) as u32).collect();
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+}
+
+impl FromStr for Natural {
+    type Err = ParseNaturalError;
+
+    /// Lit un entier écrit en décimal (les `_` sont ignorés).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut result = Self::zero();
+        let mut has_digit = false;
+        for c in s.chars().filter(|&c| c != '_') {
+            let digit = c.to_digit(10).ok_or(ParseNaturalError)?;
+            result.mul_small(10);
+            result.add_small(digit);
+            has_digit = true;
+        }
+
+        if has_digit { Ok(result) } else { Err(ParseNaturalError) }
+    }
+}
+
+impl fmt::Display for Natural {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        let mut rest = self.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_zero() {
+            chunks.push(rest.div_small(DECIMAL_CHUNK));
+        }
+
+        write!(f, "{}", chunks.last().unwrap())?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Natural {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for Natural {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl AddAssign<&Natural> for Natural {
+    fn add_assign(&mut self, rhs: &Natural) {
+        if self.limbs.len() < rhs.limbs.len() {
+            self.limbs.resize(rhs.limbs.len(), 0);
+        }
+
+        let mut carry = 0u64;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + rhs.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> LIMB_BITS;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&Natural> for &Natural {
+    type Output = Natural;
+
+    fn add(self, rhs: &Natural) -> Natural {
+        let mut result = self.clone();
+        result += rhs;
+        result
+    }
+}
+
+impl Mul<&Natural> for &Natural {
+    type Output = Natural;
+
+    fn mul(self, rhs: &Natural) -> Natural {
+        let mut limbs = vec![0u32; self.limbs.len() + rhs.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.limbs.iter().enumerate() {
+                let product = a as u64 * b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> LIMB_BITS;
+            }
+            limbs[i + rhs.limbs.len()] = carry as u32;
+        }
+        let mut result = Natural { limbs };
+        result.normalize();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_round_trip() {
+        for s in ["0", "7", "4294967296", "340282366920938463463374607431768211455", "1000000000000000000000000000000000000000000001"] {
+            let n: Natural = s.parse().unwrap();
+            assert_eq!(n.to_string(), s);
+        }
+        assert_eq!("12_345".parse::<Natural>().unwrap(), Natural::from(12345u64));
+        assert!("12a".parse::<Natural>().is_err());
+        assert!("".parse::<Natural>().is_err());
+    }
+
+    #[test]
+    fn test_digits() {
+        let n = Natural::from(u128::MAX);
+        assert_eq!(n.digits(4, 64).unwrap(), vec![3; 64]);
+        assert!(n.digits(4, 63).is_none());
+        assert_eq!(Natural::from_digits(&[3; 64], 4), n);
+        assert_eq!(n.to_u128(), Some(u128::MAX));
+
+        let mut big = n.clone();
+        big.add_small(1);
+        assert_eq!(big.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(big.to_u128(), None);
+        assert!(big > n);
+        assert_eq!(&n + &Natural::one(), big);
+    }
+
+    #[test]
+    fn test_mul() {
+        let n = Natural::from(u64::MAX);
+        assert_eq!(&n * &n, Natural::from(u64::MAX as u128 * u64::MAX as u128));
+        assert_eq!(&n * &Natural::zero(), Natural::zero());
+        assert!((&Natural::zero() * &n).is_zero());
+
+        let big: Natural = "340282366920938463463374607431768211456".parse().unwrap();
+        assert_eq!((&big * &big).to_string(), "115792089237316195423570985008687907853269984665640564039457584007913129639936");
+        assert_eq!(&big * &Natural::from(3u64), &(&big + &big) + &big);
+    }
+}
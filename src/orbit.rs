@@ -0,0 +1,132 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use crate::row::Row;
+
+/// Raison de l'arrêt de [`Automaton::evolve_until`](crate::automaton::Automaton::evolve_until).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// La ligne courante vérifie le prédicat, après `steps` itérations.
+    Matched { steps: u64 },
+    /// L'orbite est entrée dans un cycle, détecté après `steps` itérations : la ligne de départ
+    /// atteint le cycle en `pre_period` générations, puis se répète toutes les `period` générations.
+    Cycle { steps: u64, pre_period: usize, period: usize },
+    /// Le nombre maximal d'itérations a été calculé sans arrêt.
+    Exhausted { steps: u64 },
+}
+
+/// Détection de cycle de Brent, alimentée ligne par ligne.
+///
+/// Seule la « tortue » est conservée : elle saute sur la ligne courante chaque fois que
+/// le nombre de lignes reçues depuis son dernier saut atteint une puissance de deux. La première
+/// ligne identique à la tortue donne la période. Les lignes sont d'abord comparées par leur
+/// empreinte, puis état par état.
+pub(crate) struct Brent {
+    tortoise: Row,
+    hash: u64,
+    power: usize,
+    distance: usize,
+}
+
+impl Brent {
+    pub(crate) fn new(start: &Row) -> Self {
+        Self { tortoise: start.clone(), hash: row_hash(start), power: 1, distance: 0 }
+    }
+
+    /// Reçoit la ligne suivante de l'orbite et retourne la période si la ligne retrouve la tortue.
+    pub(crate) fn observe(&mut self, row: &Row) -> Option<usize> {
+        self.distance += 1;
+        let hash = row_hash(row);
+        if hash == self.hash && same_states(row, &self.tortoise) {
+            return Some(self.distance);
+        }
+        if self.distance == self.power {
+            self.tortoise = row.clone();
+            self.hash = hash;
+            self.power *= 2;
+            self.distance = 0;
+        }
+        None
+    }
+}
+
+/// Retourne la pré-période d'une orbite de période connue : le premier rang `μ` tel que
+/// les lignes `μ` et `μ + period` coïncident, en rejouant l'orbite depuis `start` avec `next`.
+pub(crate) fn pre_period(start: &Row, period: usize, mut next: impl FnMut(&Row) -> Row) -> usize {
+    let mut ahead = start.clone();
+    for _ in 0..period {
+        ahead = next(&ahead);
+    }
+    let mut behind = start.clone();
+    let mut steps = 0;
+    while !same_states(&behind, &ahead) {
+        behind = next(&behind);
+        ahead = next(&ahead);
+        steps += 1;
+    }
+    steps
+}
+
+fn row_hash(row: &Row) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for i in 0..row.len() {
+        row.get(i).unwrap().state().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn same_states(a: &Row, b: &Row) -> bool {
+    a.len() == b.len() && (0..a.len()).all(|i| a.get(i).unwrap().state() == b.get(i).unwrap().state())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::Automaton;
+    use crate::boundary::Boundary;
+    use crate::cell::Cell;
+    use crate::retention::Retention;
+    use crate::rules::WolframRule;
+    use crate::state_graph::StateGraph;
+
+    #[test]
+    fn test_cycles_match_state_graph() {
+        for (rule_number, len) in [(110, 9), (30, 8), (90, 6), (184, 7)] {
+            let graph = StateGraph::new(&WolframRule::new(rule_number), len).unwrap();
+            for state in 0..graph.state_count() as u32 {
+                let mut automaton = Automaton::new(graph.row(state), Box::new(WolframRule::new(rule_number)));
+                automaton.set_retention(Retention::Nothing);
+                let outcome = automaton.evolve_until(1000, |_, _| false);
+
+                let attractor = &graph.attractors()[graph.attractor_of(state)];
+                let Outcome::Cycle { steps, pre_period, period } = outcome else { panic!("rule {}: {:?}", rule_number, outcome) };
+                assert_eq!((pre_period, period), (graph.transient(state), attractor.period()), "rule {} from {}", rule_number, state);
+                assert_eq!(automaton.iteration() as u64, steps + 1);
+                // Brent s'arrête peu après l'entrée dans le cycle, sans conserver les lignes.
+                assert!(steps as usize <= 3 * (pre_period + period));
+            }
+        }
+    }
+
+    #[test]
+    fn test_predicate_and_limits() {
+        let mut config = vec![Cell::new(0); 41];
+        config[20] = Cell::new(1);
+        let seeded = Row::new(config);
+
+        let mut automaton = Automaton::new(seeded.clone(), Box::new(WolframRule::new(90)));
+        let outcome = automaton.evolve_until(100, |_, row| row.to_string().matches('#').count() >= 8);
+        assert_eq!(outcome, Outcome::Matched { steps: 7 });
+        assert_eq!(automaton.evolve_until(100, |generation, _| generation == 7), Outcome::Matched { steps: 0 });
+
+        // Une condition aux bords qui dépend du temps empêche la détection de cycle.
+        let mut automaton = Automaton::new(seeded.clone(), Box::new(WolframRule::new(204)));
+        automaton.set_boundary(Boundary::Sequence { left: vec![0, 1], right: vec![1] });
+        assert_eq!(automaton.evolve_until(50, |_, _| false), Outcome::Exhausted { steps: 50 });
+        let mut automaton = Automaton::new(seeded.clone(), Box::new(WolframRule::new(204)));
+        assert_eq!(automaton.evolve_until(50, |_, _| false), Outcome::Cycle { steps: 1, pre_period: 0, period: 1 });
+
+        let mut cone = Automaton::new_light_cone(Row::new(vec![Cell::new(1)]), 0, Box::new(WolframRule::new(90)));
+        assert_eq!(cone.evolve_until(50, |_, _| false), Outcome::Exhausted { steps: 50 });
+        assert_eq!(cone.iteration(), 51);
+    }
+}
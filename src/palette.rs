@@ -0,0 +1,72 @@
+/// Symboles utilisés par défaut pour afficher les états d'une cellule.
+pub const DEFAULT_SYMBOLS: [char; 10] = ['.', '#', '+', '*', 'o', '@', '%', '&', '=', '~'];
+
+/// Associe à chaque état un symbole et, éventuellement, une couleur.
+///
+/// Sans couleur, un état est rendu par son symbole. Avec des couleurs, il est rendu
+/// par son symbole sur un fond coloré (séquence ANSI « true color »), ce qui permet
+/// d'afficher des automates à `k` états directement dans un terminal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    symbols: Vec<char>,
+    colours: Vec<(u8, u8, u8)>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(DEFAULT_SYMBOLS.to_vec())
+    }
+}
+
+impl Palette {
+    /// Crée une palette de symboles, sans couleur.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let palette = Palette::new(vec![' ', '░', '▒', '▓']);
+    /// ```
+    pub fn new(symbols: Vec<char>) -> Palette {
+        Self { symbols, colours: vec![] }
+    }
+
+    /// Ajoute une couleur RGB par état.
+    pub fn with_colours(mut self, colours: Vec<(u8, u8, u8)>) -> Palette {
+        self.colours = colours;
+        self
+    }
+
+    /// Retourne le symbole de l'état, ou `?` si la palette ne le connaît pas.
+    pub fn symbol(&self, state: u8) -> char {
+        self.symbols.get(state as usize).copied().unwrap_or('?')
+    }
+
+    /// Retourne la couleur de l'état, si elle est définie.
+    pub fn colour(&self, state: u8) -> Option<(u8, u8, u8)> {
+        self.colours.get(state as usize).copied()
+    }
+
+    /// Retourne le rendu d'un état : son symbole, précédé d'une séquence ANSI
+    /// de couleur de fond si l'état a une couleur.
+    pub fn render(&self, state: u8) -> String {
+        match self.colour(state) {
+            Some((r, g, b)) => format!("\x1b[48;2;{};{};{}m{}\x1b[0m", r, g, b, self.symbol(state)),
+            None => self.symbol(state).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let palette = Palette::default();
+        assert_eq!(palette.render(0), ".");
+        assert_eq!(palette.render(2), "+");
+        assert_eq!(palette.render(200), "?");
+
+        let palette = Palette::new(vec![' ', 'X']).with_colours(vec![(0, 0, 0), (255, 128, 0)]);
+        assert_eq!(palette.render(1), "\x1b[48;2;255;128;0mX\x1b[0m");
+    }
+}
@@ -0,0 +1,202 @@
+use std::sync::{mpsc, Arc};
+use std::thread;
+use crate::bit_row::BitRow;
+use crate::boundary::{Boundary, Side};
+
+const WORD_BITS: usize = 64;
+
+/// Fait évoluer une ligne compactée de `steps` générations en la découpant en `threads`
+/// morceaux, chacun confié à un fil d'exécution créé une seule fois pour toute l'évolution.
+///
+/// L'évolution avance par blocs de `time_block` générations. Chaque morceau est prolongé
+/// d'un halo de cellules voisines au moins aussi large que le bloc (découpage temporel
+/// en trapèze) : les cellules du halo, faussées par l'absence de leurs propres voisines,
+/// ne contaminent pas le morceau avant la fin du bloc. Les fils n'ont donc besoin
+/// de se synchroniser qu'une fois par bloc, et non à chaque génération : à chaque bloc,
+/// ils reçoivent la ligne de départ par un canal et renvoient leur morceau par un autre.
+///
+/// `time` est la génération de `row`. Les lignes des générations `g` telles que `wanted(g)`
+/// sont passées, dans l'ordre, à `emit` ; la dernière ligne est retournée.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn evolve_blocks(
+    row: &BitRow,
+    rule_number: u8,
+    boundary: &Boundary,
+    time: usize,
+    steps: usize,
+    time_block: usize,
+    threads: usize,
+    wanted: impl Fn(usize) -> bool + Sync,
+    mut emit: impl FnMut(usize, &BitRow),
+) -> BitRow {
+    let len = row.len();
+    let end = time + steps;
+    if len == 0 {
+        (time + 1..=end).filter(|&generation| wanted(generation)).for_each(|generation| emit(generation, row));
+        return row.clone();
+    }
+    let words = row.words().len();
+    let chunk_words = words.div_ceil(threads.max(1)).max(1);
+    let halo = time_block.min(steps).div_ceil(WORD_BITS) * WORD_BITS;
+
+    thread::scope(|scope| {
+        let (results, finished) = mpsc::channel();
+        let wanted = &wanted;
+        // Un canal de travail par fil : (ligne de départ, génération de départ, nombre de générations).
+        let jobs: Vec<mpsc::Sender<(Arc<BitRow>, usize, usize)>> = (0..words)
+            .step_by(chunk_words)
+            .enumerate()
+            .map(|(index, first_word)| {
+                let cells = first_word * WORD_BITS..((first_word + chunk_words) * WORD_BITS).min(len);
+                let (job, work) = mpsc::channel::<(Arc<BitRow>, usize, usize)>();
+                let results = results.clone();
+                scope.spawn(move || {
+                    for (row, time, generations) in work {
+                        let block_end = time + generations;
+                        let wanted = |generation: usize| generation == block_end || wanted(generation);
+                        let chunk = evolve_chunk(&row, rule_number, boundary, time, generations, cells.clone(), halo, &wanted);
+                        results.send((index, chunk)).unwrap();
+                    }
+                });
+                job
+            })
+            .collect();
+
+        let mut current = Arc::new(row.clone());
+        let mut time = time;
+        while time < end {
+            let generations = time_block.min(end - time);
+            for job in &jobs {
+                job.send((Arc::clone(&current), time, generations)).unwrap();
+            }
+            let mut chunks = vec![vec![]; jobs.len()];
+            for _ in 0..jobs.len() {
+                let (index, chunk) = finished.recv().expect("parallel: a worker thread panicked");
+                chunks[index] = chunk;
+            }
+
+            time += generations;
+            let mut rows: Vec<(usize, Vec<u64>)> = chunks[0].iter().map(|(generation, _)| (*generation, Vec::with_capacity(words))).collect();
+            for chunk in chunks {
+                for ((_, words), (_, part)) in rows.iter_mut().zip(chunk) {
+                    words.extend(part);
+                }
+            }
+            for (generation, words) in rows {
+                let row = BitRow::from_words(words, len);
+                if wanted(generation) {
+                    emit(generation, &row);
+                }
+                if generation == time {
+                    current = Arc::new(row);
+                }
+            }
+        }
+        // Fermer les canaux de travail termine les fils.
+        drop(jobs);
+        Arc::unwrap_or_clone(current)
+    })
+}
+
+/// Fait évoluer les cellules `cells` de la ligne, prolongées de leur halo, et retourne les mots
+/// de ces cellules aux générations voulues.
+#[allow(clippy::too_many_arguments)]
+fn evolve_chunk(
+    row: &BitRow,
+    rule_number: u8,
+    boundary: &Boundary,
+    time: usize,
+    generations: usize,
+    cells: std::ops::Range<usize>,
+    halo: usize,
+    wanted: &(impl Fn(usize) -> bool + Sync),
+) -> Vec<(usize, Vec<u64>)> {
+    let len = row.len();
+    let periodic = *boundary == Boundary::Periodic;
+    // Sans condition périodique, le halo s'arrête aux bords de la ligne, où s'applique la condition aux bords.
+    let left_halo = if periodic { halo } else { halo.min(cells.start) };
+    let right_halo = if periodic { halo } else { halo.min(len - cells.end) };
+    let (at_left_edge, at_right_edge) = (!periodic && left_halo == cells.start, !periodic && cells.end + right_halo == len);
+
+    let mut segment = BitRow::new(left_halo + cells.len() + right_halo);
+    let cell = |i: i64| row.get(i.rem_euclid(len as i64) as usize).unwrap();
+    for i in 0..left_halo {
+        segment.set(i, cell(cells.start as i64 - left_halo as i64 + i as i64));
+    }
+    let offset = left_halo / WORD_BITS;
+    let mut words = segment.words().to_vec();
+    words[offset..offset + cells.len().div_ceil(WORD_BITS)]
+        .copy_from_slice(&row.words()[cells.start / WORD_BITS..cells.end.div_ceil(WORD_BITS)]);
+    segment = BitRow::from_words(words, segment.len());
+    for i in 0..right_halo {
+        segment.set(left_halo + cells.len() + i, cell((cells.end + i) as i64));
+    }
+
+    // Indice dans la ligne de la première cellule du segment, quand le segment ne fait pas le tour de l'anneau.
+    let origin = cells.start - left_halo.min(cells.start);
+    let mut result = vec![];
+    for step in 0..generations {
+        // Hors des bords de la ligne, les cellules fantômes du halo sont quelconques.
+        let cell = |i: usize| segment.get(i - origin).unwrap();
+        let left = if at_left_edge { boundary.ghost(Side::Left, 1, len, time + step, cell) } else { 0 };
+        let right = if at_right_edge { boundary.ghost(Side::Right, 1, len, time + step, cell) } else { 0 };
+        segment = segment.next(rule_number, left, right);
+
+        let generation = time + step + 1;
+        if wanted(generation) {
+            let words = &segment.words()[offset..offset + cells.len().div_ceil(WORD_BITS)];
+            let mut words = words.to_vec();
+            if let Some(last) = words.last_mut() {
+                let tail = cells.len() - (cells.len() - 1) / WORD_BITS * WORD_BITS;
+                *last &= crate::bit_row::tail_mask(tail);
+            }
+            result.push((generation, words));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    #[test]
+    fn test_block_matches_sequential() {
+        let mut random = Random::new(99);
+        let boundaries = [
+            Boundary::Periodic,
+            Boundary::Fixed(1),
+            Boundary::Reflecting,
+            Boundary::Sequence { left: vec![1, 0, 0], right: vec![0, 1] },
+        ];
+        for len in [1, 64, 100, 64 * 7 + 5, 1000] {
+            let mut row = BitRow::new(len);
+            for i in 0..len {
+                row.set(i, random.below(2) as u8);
+            }
+            for boundary in &boundaries {
+                let mut expected = vec![row.clone()];
+                for time in 0..70 {
+                    let current = expected.last().unwrap();
+                    let cell = |i: usize| current.get(i).unwrap();
+                    let left = boundary.ghost(Side::Left, 1, len, time + 3, cell);
+                    let right = boundary.ghost(Side::Right, 1, len, time + 3, cell);
+                    expected.push(current.next(30, left, right));
+                }
+
+                for threads in [1, 2, 3, 8] {
+                    for (steps, time_block) in [(1, 1), (5, 64), (70, 64), (70, 70), (67, 9), (70, 1)] {
+                        let mut generations_seen = vec![];
+                        let last = evolve_blocks(&row, 30, boundary, 3, steps, time_block, threads, |g| g % 4 == 0, |generation, actual| {
+                            assert_eq!(*actual, expected[generation - 3], "{:?}, {} cells, {} threads", boundary, len, threads);
+                            generations_seen.push(generation);
+                        });
+                        assert_eq!(last, expected[steps]);
+                        assert_eq!(generations_seen, (4..=3 + steps).filter(|g| g % 4 == 0).collect::<Vec<_>>());
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::HashSet;
+use crate::cell::Cell;
+use crate::compiled::MultiStepTable;
+use crate::natural::Natural;
+use crate::row::Row;
+use crate::rules::{elementary_number, Rule};
+
+/// Configuration périodique dans l'espace et dans le temps, à une translation près.
+#[derive(Clone)]
+pub struct PeriodicPattern {
+    row: Row,
+    period: usize,
+    shift: i64,
+}
+
+impl PeriodicPattern {
+    /// Retourne le motif qui se répète dans l'espace, dans sa rotation la plus petite
+    /// pour l'ordre lexicographique.
+    pub fn row(&self) -> &Row {
+        &self.row
+    }
+
+    /// Retourne la période spatiale minimale.
+    pub fn width(&self) -> usize {
+        self.row.len()
+    }
+
+    /// Retourne la période temporelle minimale : le motif revient, translaté, après ce nombre
+    /// de générations.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Retourne la translation vers la droite du motif à chaque période, la plus petite en valeur
+    /// absolue parmi celles que permet la période spatiale.
+    pub fn shift(&self) -> i64 {
+        self.shift
+    }
+}
+
+/// Recherche des configurations de période spatiale `p` et de période temporelle `q` d'une règle
+/// élémentaire, translations comprises : `F^q(x)[i] = x[i - d]`, le motif avançant de `d` cellules
+/// vers la droite toutes les `q` générations (planeurs, « particules », fonds périodiques).
+///
+/// La règle `F^q` a une fenêtre de `2q + 1` cellules. Dans son graphe de de Bruijn, seules
+/// les arêtes (fenêtres) `u` telles que `F^q(u)` vaut la cellule `q - d` de `u` sont conservées :
+/// les solutions de période spatiale `p` sont alors exactement les cycles de longueur `p`
+/// de ce sous-graphe, comptés par la trace de la `p`-ième puissance de sa matrice de transfert.
+///
+/// # Exemple
+/// ```text
+/// let finder = PeriodicFinder::new(&WolframRule::new(110)).unwrap();
+/// let ether = finder.solutions(14, 7, 0);
+/// let catalogue = finder.catalogue(14, 7);
+/// ```
+pub struct PeriodicFinder {
+    rule_number: u8,
+}
+
+impl PeriodicFinder {
+    /// Crée la recherche pour une règle, ou retourne `None` si la règle n'est pas élémentaire.
+    pub fn new(rule: &dyn Rule) -> Option<PeriodicFinder> {
+        elementary_number(rule).map(|rule_number| Self { rule_number })
+    }
+
+    /// Compte les anneaux de `width` cellules tels que `F^period(x)[i] = x[i - shift]`.
+    ///
+    /// Le compte est la trace de la puissance `width` de la matrice de transfert des `n` sommets
+    /// gardés dans le sous-graphe. Elle est obtenue en suivant les chemins
+    /// issus de chaque sommet, en O(n a width) additions pour `a` arêtes, ou par carrés
+    /// successifs de la matrice, en O(n³ log width) multiplications : le moins coûteux est choisi.
+    ///
+    /// # Panics
+    /// Si `width` est nul, si `period` n'est pas compris entre 1 et 10, ou si `|shift| > period`.
+    pub fn count(&self, width: usize, period: usize, shift: i64) -> Natural {
+        assert!(width > 0, "PeriodicFinder: the spatial period should be strictly positive");
+        let graph = TransferGraph::new(self.rule_number, period, shift);
+        let nodes = graph.alive().count() as u128;
+        let edges = graph.successors.iter().map(Vec::len).sum::<usize>() as u128;
+        if 2 * nodes * nodes * (width.ilog2() as u128 + 1) < edges * width as u128 {
+            graph.trace_power(width)
+        } else {
+            graph.closed_walks(width)
+        }
+    }
+
+    /// Retourne, dans l'ordre lexicographique, les anneaux de `width` cellules tels que
+    /// `F^period(x)[i] = x[i - shift]`.
+    ///
+    /// # Panics
+    /// Si `width` est nul, si `period` n'est pas compris entre 1 et 10, ou si `|shift| > period`.
+    pub fn solutions(&self, width: usize, period: usize, shift: i64) -> Vec<Row> {
+        assert!(width > 0, "PeriodicFinder: the spatial period should be strictly positive");
+        let graph = TransferGraph::new(self.rule_number, period, shift);
+        graph
+            .cycles(width)
+            .into_iter().map(|states| Row::new(states.into_iter().map(Cell::new).collect())).collect()
+    }
+
+    /// Retourne toutes les configurations de période spatiale minimale au plus `max_width`
+    /// et de période temporelle minimale au plus `max_period`, une fois chacune à rotation près,
+    /// par largeur, période puis motif croissants.
+    ///
+    /// # Panics
+    /// Si `max_period` n'est pas compris entre 1 et 10.
+    pub fn catalogue(&self, max_width: usize, max_period: usize) -> Vec<PeriodicPattern> {
+        let mut seen = HashSet::new();
+        let mut catalogue = vec![];
+        for period in 1..=max_period {
+            for shift in -(period as i64)..=period as i64 {
+                let graph = TransferGraph::new(self.rule_number, period, shift);
+                for width in 1..=max_width {
+                    for states in graph.cycles(width) {
+                        let rotation = smallest_rotation(&states);
+                        if minimal_period(&states) != width || !seen.insert(rotation.clone()) {
+                            continue;
+                        }
+                        let (period, shift) = self.minimal_motion(&states, period).unwrap();
+                        catalogue.push((width, period, rotation, shift));
+                    }
+                }
+            }
+        }
+
+        catalogue.sort();
+        catalogue
+            .into_iter()
+            .map(|(_, period, states, shift)| PeriodicPattern {
+                row: Row::new(states.into_iter().map(Cell::new).collect()),
+                period,
+                shift,
+            })
+            .collect()
+    }
+
+    /// Retourne la plus petite période temporelle (jusqu'à `max_period`) après laquelle l'anneau
+    /// revient translaté, avec la translation de plus petite valeur absolue (positive en cas d'égalité).
+    fn minimal_motion(&self, states: &[u8], max_period: usize) -> Option<(usize, i64)> {
+        let width = states.len();
+        let mut current = states.to_vec();
+        for period in 1..=max_period {
+            current = (0..width)
+                .map(|i| {
+                    let index = (current[(i + width - 1) % width] << 2) | (current[i] << 1) | current[(i + 1) % width];
+                    (self.rule_number >> index) & 1
+                })
+                .collect();
+            // Translation `d` vers la droite : current[i] = states[i - d].
+            let shifts = (0..width as i64).filter(|&d| (0..width).all(|i| current[i] == states[(i + width - d as usize) % width]));
+            if let Some(shift) = shifts.map(|d| if 2 * d > width as i64 { d - width as i64 } else { d }).min_by_key(|&d| (d.abs(), -d)) {
+                return Some((period, shift));
+            }
+        }
+        None
+    }
+}
+
+/// Sous-graphe de de Bruijn de `F^q` dont les arêtes vérifient `F^q(u) = u[q - d]`.
+///
+/// Les sommets sont les mots de `2q` cellules ; ne sont gardés que ceux qui peuvent être
+/// traversés par un chemin arbitrairement long dans les deux sens, les seuls qui portent des cycles.
+struct TransferGraph {
+    /// Arêtes issues de chaque sommet, sous la forme `(dernière cellule, sommet d'arrivée)`.
+    successors: Vec<Vec<(u8, usize)>>,
+    predecessors: Vec<Vec<usize>>,
+    alive: Vec<bool>,
+}
+
+impl TransferGraph {
+    fn new(rule_number: u8, period: usize, shift: i64) -> Self {
+        assert!(shift.unsigned_abs() as usize <= period, "PeriodicFinder: a pattern cannot move faster than light");
+        let table = MultiStepTable::new(rule_number, period);
+        let nodes = 1usize << (2 * period);
+        // Rang, depuis le bit de poids faible, de la cellule `q - d` dans la fenêtre.
+        let source = (period as i64 + shift) as usize;
+
+        let successors: Vec<Vec<(u8, usize)>> = (0..nodes)
+            .map(|node| {
+                (0..2u8)
+                    .map(|state| (state, (node << 1) | state as usize))
+                    .filter(|&(_, window)| table.apply(window) == ((window >> source) & 1) as u8)
+                    .map(|(state, window)| (state, window & (nodes - 1)))
+                    .collect()
+            })
+            .collect();
+
+        let mut alive = vec![true; nodes];
+        let mut in_degrees = vec![0usize; nodes];
+        for targets in &successors {
+            for &(_, target) in targets {
+                in_degrees[target] += 1;
+            }
+        }
+        let mut out_degrees: Vec<usize> = successors.iter().map(Vec::len).collect();
+        let mut predecessors = vec![vec![]; nodes];
+        for (node, targets) in successors.iter().enumerate() {
+            for &(_, target) in targets {
+                predecessors[target].push(node);
+            }
+        }
+        let mut dead: Vec<usize> = (0..nodes).filter(|&node| in_degrees[node] == 0 || out_degrees[node] == 0).collect();
+        for &node in &dead {
+            alive[node] = false;
+        }
+        while let Some(node) = dead.pop() {
+            for &(_, target) in &successors[node] {
+                in_degrees[target] -= 1;
+                if alive[target] && in_degrees[target] == 0 {
+                    alive[target] = false;
+                    dead.push(target);
+                }
+            }
+            for &source in &predecessors[node] {
+                out_degrees[source] -= 1;
+                if alive[source] && out_degrees[source] == 0 {
+                    alive[source] = false;
+                    dead.push(source);
+                }
+            }
+        }
+
+        let successors: Vec<Vec<(u8, usize)>> = successors
+            .into_iter()
+            .enumerate()
+            .map(|(node, targets)| if alive[node] { targets.into_iter().filter(|&(_, target)| alive[target]).collect() } else { vec![] })
+            .collect();
+        let mut predecessors = vec![vec![]; nodes];
+        for (node, targets) in successors.iter().enumerate() {
+            for &(_, target) in targets {
+                predecessors[target].push(node);
+            }
+        }
+        Self { successors, predecessors, alive }
+    }
+
+    fn alive(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.alive.len()).filter(|&node| self.alive[node])
+    }
+
+    /// Compte les cycles de `width` arêtes en suivant les chemins issus de chaque sommet.
+    fn closed_walks(&self, width: usize) -> Natural {
+        let mut total = Natural::zero();
+        for start in self.alive() {
+            let mut counts = vec![Natural::zero(); self.successors.len()];
+            counts[start] = Natural::one();
+            for _ in 0..width {
+                let mut next = vec![Natural::zero(); counts.len()];
+                for (node, count) in counts.iter().enumerate().filter(|(_, count)| !count.is_zero()) {
+                    for &(_, target) in &self.successors[node] {
+                        next[target] += count;
+                    }
+                }
+                counts = next;
+            }
+            total += &counts[start];
+        }
+        total
+    }
+
+    /// Compte les cycles de `width` arêtes : la trace de la puissance `width` de la matrice
+    /// de transfert des sommets vivants, calculée par carrés successifs.
+    fn trace_power(&self, width: usize) -> Natural {
+        let alive: Vec<usize> = self.alive().collect();
+        let mut rank = vec![usize::MAX; self.successors.len()];
+        for (i, &node) in alive.iter().enumerate() {
+            rank[node] = i;
+        }
+        let mut base = vec![vec![Natural::zero(); alive.len()]; alive.len()];
+        for (i, &node) in alive.iter().enumerate() {
+            for &(_, target) in &self.successors[node] {
+                base[i][rank[target]] = Natural::one();
+            }
+        }
+
+        let mut power: Option<Vec<Vec<Natural>>> = None;
+        let mut exponent = width;
+        loop {
+            if exponent & 1 == 1 {
+                power = Some(match power {
+                    None => base.clone(),
+                    Some(power) => multiply(&power, &base),
+                });
+            }
+            exponent >>= 1;
+            if exponent == 0 {
+                break;
+            }
+            base = multiply(&base, &base);
+        }
+
+        let mut trace = Natural::zero();
+        for (i, row) in power.unwrap().iter().enumerate() {
+            trace += &row[i];
+        }
+        trace
+    }
+
+    /// Retourne, dans l'ordre lexicographique, les cellules des cycles de `width` arêtes.
+    fn cycles(&self, width: usize) -> Vec<Vec<u8>> {
+        let mut cycles = vec![];
+        // viable[i][node] : un chemin de `width - i` arêtes mène de `node` au sommet de départ.
+        // Les couches sont construites en remontant les arêtes depuis ce sommet, puis effacées.
+        let mut viable = vec![vec![false; self.successors.len()]; width + 1];
+        let mut layers = vec![vec![]; width + 1];
+        for start in self.alive() {
+            viable[width][start] = true;
+            layers[width].push(start);
+            for i in (0..width).rev() {
+                let (layer, above) = layers.split_at_mut(i + 1);
+                for &node in &above[0] {
+                    for &source in &self.predecessors[node] {
+                        if !viable[i][source] {
+                            viable[i][source] = true;
+                            layer[i].push(source);
+                        }
+                    }
+                }
+            }
+            if viable[0][start] {
+                self.walk(&viable, 0, start, &mut vec![], &mut cycles);
+            }
+            for (i, layer) in layers.iter_mut().enumerate() {
+                for node in layer.drain(..) {
+                    viable[i][node] = false;
+                }
+            }
+        }
+        cycles.sort();
+        cycles
+    }
+
+    /// Énumère les cycles viables depuis `node`, en ajoutant à `path` la dernière cellule de chaque arête :
+    /// la cellule `i` de l'anneau est la dernière cellule de la `i`-ème arête.
+    fn walk(&self, viable: &[Vec<bool>], step: usize, node: usize, path: &mut Vec<u8>, cycles: &mut Vec<Vec<u8>>) {
+        if step + 1 == viable.len() {
+            cycles.push(path.clone());
+            return;
+        }
+        for &(state, target) in &self.successors[node] {
+            if viable[step + 1][target] {
+                path.push(state);
+                self.walk(viable, step + 1, target, path, cycles);
+                path.pop();
+            }
+        }
+    }
+}
+
+/// Retourne le produit de deux matrices carrées.
+fn multiply(left: &[Vec<Natural>], right: &[Vec<Natural>]) -> Vec<Vec<Natural>> {
+    let mut product = vec![vec![Natural::zero(); right.len()]; left.len()];
+    for (i, row) in left.iter().enumerate() {
+        for (j, a) in row.iter().enumerate().filter(|(_, a)| !a.is_zero()) {
+            for (k, b) in right[j].iter().enumerate().filter(|(_, b)| !b.is_zero()) {
+                product[i][k] += &(a * b);
+            }
+        }
+    }
+    product
+}
+
+/// Retourne la plus petite période d'une suite circulaire.
+fn minimal_period(states: &[u8]) -> usize {
+    let n = states.len();
+    (1..=n).find(|&p| n.is_multiple_of(p) && (0..n).all(|i| states[i] == states[(i + p) % n])).unwrap()
+}
+
+/// Retourne la plus petite rotation d'une suite circulaire pour l'ordre lexicographique.
+fn smallest_rotation(states: &[u8]) -> Vec<u8> {
+    (0..states.len())
+        .map(|r| {
+            let mut rotated = states.to_vec();
+            rotated.rotate_left(r);
+            rotated
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::WolframRule;
+
+    fn ring(index: usize, width: usize) -> Row {
+        Row::new((0..width).map(|i| Cell::new(((index >> (width - 1 - i)) & 1) as u8)).collect())
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for rule_number in [110, 54, 30, 184, 90, 204] {
+            let rule = WolframRule::new(rule_number);
+            let finder = PeriodicFinder::new(&rule).unwrap();
+            for width in 1..=8 {
+                for period in 1..=3 {
+                    for shift in -(period as i64)..=period as i64 {
+                        let expected: Vec<String> = (0..1usize << width)
+                            .map(|index| ring(index, width))
+                            .filter(|row| {
+                                let mut image = row.clone();
+                                for _ in 0..period {
+                                    image = image.next(&rule);
+                                }
+                                (0..width).all(|i| image.get(i).unwrap().state() == row.get((i + width * 4 - shift.rem_euclid(width as i64) as usize) % width).unwrap().state())
+                            })
+                            .map(|row| row.to_string())
+                            .collect();
+                        let actual: Vec<String> = finder.solutions(width, period, shift).iter().map(Row::to_string).collect();
+                        assert_eq!(actual, expected, "rule {}, p = {}, q = {}, d = {}", rule_number, width, period, shift);
+                        assert_eq!(finder.count(width, period, shift), Natural::from(expected.len() as u64));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_trace_by_squaring() {
+        for rule_number in [110, 54, 30, 184, 90] {
+            for (period, shift) in [(1, 0), (2, 1), (3, -1), (3, 0)] {
+                let graph = TransferGraph::new(rule_number, period, shift);
+                for width in [1, 2, 5, 13, 30] {
+                    assert_eq!(graph.trace_power(width), graph.closed_walks(width), "rule {}, p = {}, q = {}, d = {}", rule_number, width, period, shift);
+                }
+            }
+        }
+
+        // Tous les anneaux sont fixes pour l'identité (règle 204) : 2^200 sur 200 cellules.
+        let mut expected = Natural::one();
+        for _ in 0..200 {
+            expected.mul_small(2);
+        }
+        assert_eq!(PeriodicFinder::new(&WolframRule::new(204)).unwrap().count(200, 1, 0), expected);
+    }
+
+    #[test]
+    fn test_backgrounds() {
+        let describe = |pattern: &PeriodicPattern| (pattern.row().to_string(), pattern.period(), pattern.shift());
+
+        // L'éther de la règle 110 : 14 cellules, qui reviennent décalées de 4 cellules vers la gauche à chaque génération.
+        let rule = WolframRule::new(110);
+        let finder = PeriodicFinder::new(&rule).unwrap();
+        let catalogue: Vec<_> = finder.catalogue(14, 7).iter().map(describe).collect();
+        assert!(catalogue.contains(&("...#..##.#####".to_string(), 1, -4)));
+        assert!(catalogue.iter().all(|(row, _, _)| row.len() <= 14));
+        // Sur 14 cellules, l'anneau vide et les 14 rotations de l'éther sont de période 7.
+        let solutions = finder.solutions(14, 7, 0);
+        assert_eq!(solutions.len(), 15);
+        assert_eq!(solutions[0].to_string(), "..............");
+        let mut image = solutions[1].clone();
+        for _ in 0..7 {
+            image = image.next(&rule);
+        }
+        assert_eq!(image.to_string(), solutions[1].to_string());
+
+        let finder = PeriodicFinder::new(&WolframRule::new(54)).unwrap();
+        let catalogue: Vec<_> = finder.catalogue(8, 4).iter().map(describe).collect();
+        // Sur un anneau, une ligne peut revenir tournée de plus d'une cellule par génération.
+        let expected = [
+            (".", 1, 0),
+            ("..##", 1, 2),
+            ("...#", 2, 2),
+            (".###", 2, 2),
+            (".....#", 4, 0),
+            ("...#.#", 4, 0),
+            ("...###", 4, 0),
+            (".#####", 4, 0),
+            ("......#", 4, 0),
+            ("....###", 4, 0),
+            ("...#..#", 4, 0),
+            (".######", 4, 0),
+            ("...#.###", 1, -3),
+            ("...###.#", 1, 3),
+            (".....#.#", 2, 4),
+            ("...#####", 2, 4),
+        ];
+        assert_eq!(catalogue, expected.map(|(row, period, shift)| (row.to_string(), period, shift)));
+    }
+}
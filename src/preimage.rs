@@ -0,0 +1,338 @@
+use std::collections::{HashMap, VecDeque};
+use crate::cell::Cell;
+use crate::de_bruijn::DeBruijnGraph;
+use crate::natural::Natural;
+use crate::row::Row;
+use crate::rules::Rule;
+
+/// Forme des lignes dont on cherche les antécédents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// La ligne est un anneau, comme avec [`Boundary::Periodic`](crate::boundary::Boundary::Periodic) :
+    /// ses antécédents sont des anneaux de même longueur.
+    Ring,
+    /// La ligne est un motif d'une ligne infinie : ses antécédents s'étendent
+    /// de `left_reach` cellules à gauche et de `right_reach` cellules à droite.
+    Open,
+}
+
+/// Recherche des antécédents d'une ligne par une règle déterministe, à l'aide de son graphe
+/// de de Bruijn.
+///
+/// Un antécédent est un chemin du graphe dont les étiquettes forment la ligne ; sur un anneau,
+/// le chemin est un cycle. Un motif sans antécédent ouvert est un motif « Jardin d'Éden »
+/// (ou orphelin) : il ne peut apparaître qu'à la première génération.
+///
+/// # Exemple
+/// ```text
+/// let finder = PreimageFinder::new(&WolframRule::new(110)).unwrap();
+/// let count = finder.count(&row, Topology::Ring);
+/// let orphans = finder.smallest_orphans();
+/// ```
+pub struct PreimageFinder {
+    graph: DeBruijnGraph,
+}
+
+impl PreimageFinder {
+    /// Crée la recherche pour une règle, ou retourne `None` si la règle n'est pas déterministe.
+    pub fn new(rule: &dyn Rule) -> Option<PreimageFinder> {
+        DeBruijnGraph::new(rule).map(|graph| Self { graph })
+    }
+
+    /// Retourne le graphe de de Bruijn de la règle.
+    pub fn graph(&self) -> &DeBruijnGraph {
+        &self.graph
+    }
+
+    /// Compte les antécédents d'une ligne.
+    ///
+    /// # Panics
+    /// Si la ligne contient un état que la règle ne connaît pas.
+    pub fn count(&self, row: &Row, topology: Topology) -> Natural {
+        let labels = self.labels(row);
+        let nodes = self.graph.node_count();
+        match topology {
+            Topology::Open => self.paths(&labels, vec![Natural::one(); nodes]).iter().fold(Natural::zero(), |sum, count| &sum + count),
+            Topology::Ring if labels.is_empty() => Natural::one(),
+            Topology::Ring => {
+                let mut total = Natural::zero();
+                for start in 0..nodes {
+                    let mut counts = vec![Natural::zero(); nodes];
+                    counts[start] = Natural::one();
+                    total += &self.paths(&labels, counts)[start];
+                }
+                total
+            }
+        }
+    }
+
+    /// Retourne `true` si la ligne a au moins un antécédent.
+    ///
+    /// # Panics
+    /// Si la ligne contient un état que la règle ne connaît pas.
+    pub fn has_preimage(&self, row: &Row, topology: Topology) -> bool {
+        let labels = self.labels(row);
+        match topology {
+            Topology::Open => self.viable(&labels, None)[0].contains(&true),
+            Topology::Ring => labels.is_empty() || (0..self.graph.node_count()).any(|start| self.viable(&labels, Some(start))[0][start]),
+        }
+    }
+
+    /// Retourne tous les antécédents d'une ligne, dans l'ordre lexicographique.
+    ///
+    /// Leur nombre peut croître exponentiellement avec la longueur de la ligne : voir
+    /// [`PreimageFinder::count`] avant de les énumérer. L'itération des antécédents précède
+    /// celle de la ligne.
+    ///
+    /// # Panics
+    /// Si la ligne contient un état que la règle ne connaît pas.
+    pub fn preimages(&self, row: &Row, topology: Topology) -> Vec<Row> {
+        let labels = self.labels(row);
+        let n = labels.len();
+        let mut preimages = vec![];
+        match topology {
+            Topology::Open => {
+                let viable = self.viable(&labels, None);
+                for start in (0..self.graph.node_count()).filter(|&start| viable[0][start]) {
+                    let mut path = self.graph.states(start);
+                    self.walk(&labels, &viable, 0, start, &mut path, &mut preimages);
+                }
+            }
+            Topology::Ring if n == 0 => preimages.push(vec![]),
+            Topology::Ring => {
+                // La dernière cellule de la `i`-ème arête est la cellule `i + right_reach` de l'anneau.
+                let shift = self.graph.right_reach() % n;
+                for start in 0..self.graph.node_count() {
+                    let viable = self.viable(&labels, Some(start));
+                    if viable[0][start] {
+                        let mut cycles = vec![];
+                        self.walk(&labels, &viable, 0, start, &mut vec![], &mut cycles);
+                        preimages.extend(cycles.into_iter().map(|mut states| {
+                            states.rotate_right(shift);
+                            states
+                        }));
+                    }
+                }
+                preimages.sort();
+            }
+        }
+
+        preimages
+            .into_iter()
+            .map(|states| {
+                let mut preimage = Row::new(states.into_iter().map(Cell::new).collect());
+                preimage.set_iteration(row.iteration().saturating_sub(1));
+                preimage
+            })
+            .collect()
+    }
+
+    /// Retourne les motifs orphelins (sans antécédent ouvert) les plus courts, dans l'ordre
+    /// lexicographique, ou une liste vide si la règle est surjective.
+    ///
+    /// Les ensembles de sommets où peut aboutir un chemin étiqueté par un motif sont explorés
+    /// en largeur depuis l'ensemble de tous les sommets : un motif est orphelin quand l'ensemble
+    /// devient vide. Le premier niveau où cela arrive donne la longueur minimale.
+    ///
+    /// # Panics
+    /// Si le graphe de de Bruijn a plus de 64 sommets.
+    pub fn smallest_orphans(&self) -> Vec<Row> {
+        let nodes = self.graph.node_count();
+        assert!(nodes <= 64, "PreimageFinder: too many de Bruijn nodes to search for orphans");
+        let k = self.graph.colours();
+        let everything = if nodes == 64 { u64::MAX } else { (1 << nodes) - 1 };
+
+        let mut depths = HashMap::from([(everything, 0usize)]);
+        let mut queue = VecDeque::from([everything]);
+        let mut length = None;
+        while let Some(set) = queue.pop_front() {
+            if set == 0 {
+                length = Some(depths[&set]);
+                break;
+            }
+            for label in 0..k {
+                let next = self.graph.follow(set, label);
+                if !depths.contains_key(&next) {
+                    depths.insert(next, depths[&set] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        let Some(length) = length else {
+            return vec![];
+        };
+
+        let mut orphans = vec![];
+        let mut empties = HashMap::new();
+        self.collect_orphans(everything, length, &mut vec![], &mut orphans, &mut empties);
+        orphans.into_iter().map(|states: Vec<u8>| Row::new(states.into_iter().map(Cell::new).collect())).collect()
+    }
+
+    /// Parcourt les motifs de `remaining` états qui vident l'ensemble de sommets `set`.
+    ///
+    /// `empties` retient, pour chaque couple `(set, remaining)` déjà exploré, si un tel motif existe.
+    fn collect_orphans(&self, set: u64, remaining: usize, pattern: &mut Vec<u8>, orphans: &mut Vec<Vec<u8>>, empties: &mut HashMap<(u64, usize), bool>) -> bool {
+        if remaining == 0 {
+            if set == 0 {
+                orphans.push(pattern.clone());
+            }
+            return set == 0;
+        }
+        if empties.get(&(set, remaining)) == Some(&false) {
+            return false;
+        }
+
+        let mut found = false;
+        for label in 0..self.graph.colours() {
+            pattern.push(label);
+            found |= self.collect_orphans(self.graph.follow(set, label), remaining - 1, pattern, orphans, empties);
+            pattern.pop();
+        }
+        empties.insert((set, remaining), found);
+        found
+    }
+
+    /// Fait avancer les nombres de chemins, sommet par sommet, le long des étiquettes.
+    fn paths(&self, labels: &[u8], mut counts: Vec<Natural>) -> Vec<Natural> {
+        for &label in labels {
+            let mut next = vec![Natural::zero(); counts.len()];
+            for (node, count) in counts.iter().enumerate().filter(|(_, count)| !count.is_zero()) {
+                for target in self.graph.successors(node, label) {
+                    next[target] += count;
+                }
+            }
+            counts = next;
+        }
+        counts
+    }
+
+    /// Retourne, pour chaque position `i` et chaque sommet, `true` si un chemin étiqueté
+    /// par `labels[i..]` part de ce sommet (et aboutit à `end`, s'il est donné).
+    fn viable(&self, labels: &[u8], end: Option<usize>) -> Vec<Vec<bool>> {
+        let nodes = self.graph.node_count();
+        let mut viable = vec![vec![false; nodes]; labels.len() + 1];
+        viable[labels.len()] = (0..nodes).map(|node| end.is_none_or(|end| node == end)).collect();
+        for i in (0..labels.len()).rev() {
+            for node in 0..nodes {
+                viable[i][node] = self.graph.successors(node, labels[i]).any(|target| viable[i + 1][target]);
+            }
+        }
+        viable
+    }
+
+    /// Énumère les chemins viables depuis `node`, à la position `step`, en ajoutant à `path`
+    /// le dernier état de chaque arête.
+    fn walk(&self, labels: &[u8], viable: &[Vec<bool>], step: usize, node: usize, path: &mut Vec<u8>, paths: &mut Vec<Vec<u8>>) {
+        if step == labels.len() {
+            paths.push(path.clone());
+            return;
+        }
+        for (state, target, label) in self.graph.edges(node) {
+            if label == labels[step] && viable[step + 1][target] {
+                path.push(state);
+                self.walk(labels, viable, step + 1, target, path, paths);
+                path.pop();
+            }
+        }
+    }
+
+    fn labels(&self, row: &Row) -> Vec<u8> {
+        (0..row.len())
+            .map(|i| {
+                let state = row.get(i).unwrap().state();
+                assert!(state < self.graph.colours(), "PreimageFinder: state {} is not a state of the rule", state);
+                state
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use crate::natural::Natural;
+    use crate::rules::{TotalisticRule, WolframRule};
+
+    fn rows(len: usize, colours: u8) -> Vec<Row> {
+        (0..(colours as usize).pow(len as u32))
+            .map(|mut index| {
+                let mut states = vec![0; len];
+                for state in states.iter_mut().rev() {
+                    *state = (index % colours as usize) as u8;
+                    index /= colours as usize;
+                }
+                Row::new(states.into_iter().map(Cell::new).collect())
+            })
+            .collect()
+    }
+
+    /// Image d'un motif ouvert : une cellule par fenêtre complète.
+    fn open_image(row: &Row, rule: &dyn Rule) -> String {
+        let states: Vec<u8> = (0..row.len()).map(|i| row.get(i).unwrap().state()).collect();
+        Row::new(states.windows(rule.window()).map(|window| Cell::new(rule.apply(window))).collect()).to_string()
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let rules: Vec<(Box<dyn Rule>, u8, usize)> = vec![
+            (Box::new(WolframRule::new(30)), 2, 7),
+            (Box::new(WolframRule::new(110)), 2, 7),
+            (Box::new(WolframRule::new(184)), 2, 7),
+            (Box::new(WolframRule::new(0)), 2, 5),
+            (Box::new(WolframRule::new(232)), 2, 6),
+            (Box::new(WolframRule::with_window(6, 2)), 2, 6),
+            (Box::new(TotalisticRule::new(&Natural::from(1599u64), 3, 3)), 3, 3),
+        ];
+
+        for (rule, colours, max_len) in &rules {
+            let finder = PreimageFinder::new(rule.as_ref()).unwrap();
+            let extra = rule.window() - 1;
+            for len in 1..=*max_len {
+                let candidates = rows(len, *colours);
+                let open_candidates = rows(len + extra, *colours);
+                for row in &candidates {
+                    let expected: Vec<String> = candidates.iter().filter(|x| x.next(rule.as_ref()).to_string() == row.to_string()).map(Row::to_string).collect();
+                    let actual: Vec<String> = finder.preimages(row, Topology::Ring).iter().map(Row::to_string).collect();
+                    assert_eq!(actual, expected, "ring preimages of {}", row.to_string());
+                    assert_eq!(finder.count(row, Topology::Ring), Natural::from(expected.len() as u64));
+                    assert_eq!(finder.has_preimage(row, Topology::Ring), !expected.is_empty());
+
+                    let expected: Vec<String> = open_candidates.iter().filter(|x| open_image(x, rule.as_ref()) == row.to_string()).map(Row::to_string).collect();
+                    let actual: Vec<String> = finder.preimages(row, Topology::Open).iter().map(Row::to_string).collect();
+                    assert_eq!(actual, expected, "open preimages of {}", row.to_string());
+                    assert_eq!(finder.count(row, Topology::Open), Natural::from(expected.len() as u64));
+                    assert_eq!(finder.has_preimage(row, Topology::Open), !expected.is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_smallest_orphans() {
+        for rule_number in 0..=255u8 {
+            let rule = WolframRule::new(rule_number);
+            let finder = PreimageFinder::new(&rule).unwrap();
+            let orphans: Vec<String> = finder.smallest_orphans().iter().map(Row::to_string).collect();
+
+            // Plus petite longueur (jusqu'à 10) où un motif n'est l'image d'aucun mot
+            // de deux cellules plus long.
+            let expected = (1..=10)
+                .map(|len| {
+                    let images: HashSet<String> = rows(len + 2, 2).iter().map(|word| open_image(word, &rule)).collect();
+                    rows(len, 2).into_iter().map(|row| row.to_string()).filter(|row| !images.contains(row)).collect::<Vec<_>>()
+                })
+                .find(|orphans| !orphans.is_empty());
+            match expected {
+                Some(expected) => assert_eq!(orphans, expected, "rule {}", rule_number),
+                None => assert!(orphans.is_empty() || orphans[0].len() > 10, "rule {}", rule_number),
+            }
+        }
+
+        let orphans = |rule_number| PreimageFinder::new(&WolframRule::new(rule_number)).unwrap().smallest_orphans();
+        for surjective in [30, 90, 150, 170, 204] {
+            assert!(orphans(surjective).is_empty());
+        }
+        assert_eq!(orphans(0).iter().map(Row::to_string).collect::<Vec<_>>(), vec!["#"]);
+    }
+}
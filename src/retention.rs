@@ -0,0 +1,47 @@
+/// Lignes conservées par un automate au fil de son évolution.
+///
+/// La ligne courante est toujours conservée, quelle que soit la politique : c'est d'elle
+/// que part la prochaine itération.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Retention {
+    /// Toutes les lignes sont conservées.
+    #[default]
+    All,
+    /// Seules les `n` dernières lignes sont visibles, dans une fenêtre glissante.
+    ///
+    /// Pour que [`Automaton::grid`](crate::automaton::Automaton::grid) reste une tranche contiguë,
+    /// les lignes sorties de la fenêtre ne sont pas retirées une à une mais par paquets, quand
+    /// `2n` lignes sont stockées : la mémoire utilisée est donc bornée par `2n - 1` lignes,
+    /// et non `n`, pour un coût amorti constant par génération.
+    Last(usize),
+    /// Seules les lignes dont la génération est un multiple de `k` sont conservées,
+    /// en plus de la ligne courante.
+    Every(usize),
+    /// Seule la ligne courante est conservée.
+    Nothing,
+}
+
+impl Retention {
+    /// Retourne `true` si la ligne de la génération donnée doit être conservée
+    /// une fois qu'elle n'est plus la ligne courante.
+    pub(crate) fn keeps(&self, generation: usize) -> bool {
+        match *self {
+            Retention::All | Retention::Last(_) => true,
+            Retention::Every(k) => generation.is_multiple_of(k),
+            Retention::Nothing => false,
+        }
+    }
+
+    /// Retourne `true` si les lignes conservées sont des générations consécutives.
+    pub fn is_contiguous(&self) -> bool {
+        !matches!(self, Retention::Every(k) if *k > 1)
+    }
+
+    pub(crate) fn validate(&self) {
+        match *self {
+            Retention::Last(n) => assert!(n > 0, "Retention: at least the current row is kept"),
+            Retention::Every(k) => assert!(k > 0, "Retention: the sampling period should be strictly positive"),
+            _ => {}
+        }
+    }
+}
@@ -1,11 +1,13 @@
+use crate::boundary::{Boundary, Side};
 use crate::cell::Cell;
+use crate::palette::Palette;
 use crate::rules::Rule;
 
 /// Représente une ligne d'un automate cellulaire unidimensionnel.
 ///
 /// Une `Row` contient une configuration de cellules (`Cell`) ainsi qu’un compteur
-/// d’itération. L’itération n’est pas modifiée automatiquement, mais peut servir
-/// à suivre l’évolution dans un contexte plus large (non utilisée ici).
+/// d’itération. L’itération est incrémentée par [`Row::next`] et sert aux conditions
+/// aux bords qui dépendent du temps ([`Boundary::Sequence`]).
 pub struct Row {
     configuration: Vec<Cell>,
     iteration: usize,
@@ -61,10 +63,41 @@ impl Row {
         }
     }
 
+    /// Retourne l’itération associée à la ligne.
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// Modifie l’itération associée à la ligne.
+    pub fn set_iteration(&mut self, iteration: usize) {
+        self.iteration = iteration;
+    }
+
+    /// Retourne une copie de la ligne prolongée de `left` cellules à gauche et de
+    /// `right` cellules à droite, toutes à l’état `state`.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let row = Row::new(vec![Cell::new(1)]);
+    /// assert_eq!(row.extended(1, 2, 0).to_string(), ".#..");
+    /// ```
+    pub fn extended(&self, left: usize, right: usize, state: u8) -> Row {
+        let mut configuration = Vec::with_capacity(self.configuration.len() + left + right);
+        configuration.extend((0..left).map(|_| Cell::new(state)));
+        configuration.extend(self.configuration.iter().cloned());
+        configuration.extend((0..right).map(|_| Cell::new(state)));
+
+        Self {
+            configuration,
+            iteration: self.iteration,
+        }
+    }
+
     /// Calcule la prochaine ligne selon une règle d’automate cellulaire.
     ///
-    /// Applique la règle à chaque triplet `(gauche, centre, droite)` de cellules.
-    /// Les bords sont connectés de manière cyclique.
+    /// Applique la règle au voisinage de chaque cellule (le triplet `(gauche, centre, droite)`
+    /// pour une règle de rayon 1).
+    /// Les bords sont connectés de manière cyclique ([`Boundary::Periodic`]).
     ///
     /// # Exemple
     /// ```text
@@ -77,23 +110,43 @@ impl Row {
     /// Le comportement cyclique signifie que la première cellule utilise comme "voisine gauche"
     /// la dernière cellule de la ligne, et inversement.
     pub fn next(&self, rule: &dyn Rule) -> Row {
-        let mut next_configuration = Vec::with_capacity(self.configuration.len());
+        self.next_with_boundary(rule, &Boundary::Periodic)
+    }
 
-        for i in 0..self.configuration.len() {
-            let left = if i == 0 { 0 } else { i - 1 };
-            let center = i;
-            let right = if i == self.configuration.len() - 1 { 0 } else { i + 1 };
+    /// Calcule la prochaine ligne selon une règle et une condition aux bords.
+    ///
+    /// Chaque cellule reçoit sa fenêtre de `rule.window()` voisines. Les voisines
+    /// manquantes près des bords sont données par `boundary`, évaluée à l’itération
+    /// de la ligne courante. La ligne retournée a pour itération celle de la ligne
+    /// courante plus un.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let next = row.next_with_boundary(&WolframRule::new(90), &Boundary::Fixed(0));
+    /// ```
+    pub fn next_with_boundary(&self, rule: &dyn Rule, boundary: &Boundary) -> Row {
+        let padded = self.padded_states(rule.left_reach(), rule.right_reach(), boundary);
+        let next_configuration = padded
+            .windows(rule.window())
+            .map(|neighbourhood| Cell::new(rule.apply(neighbourhood)))
+            .collect();
 
-            let new_state = rule.apply(
-                self.configuration[left].state(),
-                self.configuration[center].state(),
-                self.configuration[right].state(),
-            );
+        let mut next = Self::new(next_configuration);
+        next.iteration = self.iteration + 1;
+        next
+    }
 
-            next_configuration.push(Cell::new(new_state));
-        }
+    /// Retourne les états de la ligne précédés de `left` et suivis de `right`
+    /// cellules fantômes données par `boundary`.
+    pub(crate) fn padded_states(&self, left: usize, right: usize, boundary: &Boundary) -> Vec<u8> {
+        let len = self.configuration.len();
+        let cell = |i: usize| self.configuration[i].state();
+        let mut padded = Vec::with_capacity(len + left + right);
 
-        Self::new(next_configuration)
+        padded.extend((1..=left).rev().map(|d| boundary.ghost(Side::Left, d, len, self.iteration, cell)));
+        padded.extend(self.configuration.iter().map(Cell::state));
+        padded.extend((1..=right).map(|d| boundary.ghost(Side::Right, d, len, self.iteration, cell)));
+        padded
     }
 
     /// Retourne une représentation textuelle de la ligne, en concaténant les
@@ -107,4 +160,15 @@ impl Row {
     pub fn to_string(&self) -> String {
         self.configuration.iter().map(|c| c.display()).collect()
     }
+
+    /// Retourne une représentation de la ligne selon une palette de symboles ou de couleurs.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let palette = Palette::new(vec![' ', '░', '▒']);
+    /// println!("{}", row.to_string_with(&palette));
+    /// ```
+    pub fn to_string_with(&self, palette: &Palette) -> String {
+        self.configuration.iter().map(|c| c.display_with(palette)).collect()
+    }
 }
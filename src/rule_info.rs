@@ -0,0 +1,267 @@
+use crate::natural::Natural;
+use crate::rules::{Rule, WolframRule};
+
+/// Métadonnées d'une règle déterministe, calculées à partir de sa table de transition.
+///
+/// La table contient une entrée par voisinage : le voisinage, lu de gauche à droite,
+/// est interprété comme un entier en base `k` (la cellule la plus à gauche est le chiffre
+/// de poids fort), exactement comme dans la numérotation de Wolfram.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RuleInfo {
+    table: Vec<u8>,
+    window: usize,
+    colours: u8,
+}
+
+impl RuleInfo {
+    /// Calcule les métadonnées d'une règle en la sondant sur tous ses voisinages.
+    ///
+    /// Retourne `None` si la règle n'est pas déterministe.
+    pub fn of<R: Rule + ?Sized>(rule: &R) -> Option<RuleInfo> {
+        if !rule.is_deterministic() {
+            return None;
+        }
+
+        let window = rule.window();
+        let k = rule.colours() as usize;
+        let size = k.pow(window as u32);
+        let mut neighbourhood = vec![0u8; window];
+        let table = (0..size)
+            .map(|index| {
+                let mut rest = index;
+                for cell in neighbourhood.iter_mut().rev() {
+                    *cell = (rest % k) as u8;
+                    rest /= k;
+                }
+                rule.apply(&neighbourhood)
+            })
+            .collect();
+
+        Some(Self { table, window, colours: k as u8 })
+    }
+
+    /// Retourne les métadonnées de la règle élémentaire de numéro donné.
+    pub fn elementary(number: u8) -> RuleInfo {
+        let table = (0..8).map(|i| (number >> i) & 1).collect();
+        Self { table, window: 3, colours: 2 }
+    }
+
+    /// Retourne le numéro de Wolfram de la règle.
+    pub fn number(&self) -> Natural {
+        Natural::from_digits(&self.table, self.colours)
+    }
+
+    /// Retourne le numéro d'une règle élémentaire, ou `None` pour une autre règle.
+    pub fn elementary_number(&self) -> Option<u8> {
+        if self.window == 3 && self.colours == 2 {
+            Some(self.number().low_u64() as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Retourne la table de transition.
+    pub fn table(&self) -> &[u8] {
+        &self.table
+    }
+
+    /// Retourne le nombre de cellules du voisinage.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Retourne le nombre d'états.
+    pub fn colours(&self) -> u8 {
+        self.colours
+    }
+
+    /// Retourne la règle réfléchie (gauche et droite échangées) ; la règle 30 donne la règle 86.
+    pub fn reflected(&self) -> RuleInfo {
+        let table = (0..self.table.len())
+            .map(|index| {
+                let mut neighbourhood = self.neighbourhood(index);
+                neighbourhood.reverse();
+                self.table[self.index(&neighbourhood)]
+            })
+            .collect();
+        self.with_table(table)
+    }
+
+    /// Retourne la règle conjuguée (états `s` et `k - 1 - s` échangés) ; la règle 30 donne la règle 135.
+    pub fn complemented(&self) -> RuleInfo {
+        let top = self.colours - 1;
+        let table = (0..self.table.len())
+            .map(|index| {
+                let neighbourhood: Vec<u8> = self.neighbourhood(index).iter().map(|&s| top - s).collect();
+                top - self.table[self.index(&neighbourhood)]
+            })
+            .collect();
+        self.with_table(table)
+    }
+
+    /// Retourne la règle réfléchie et conjuguée ; la règle 30 donne la règle 149.
+    pub fn reflected_complemented(&self) -> RuleInfo {
+        self.reflected().complemented()
+    }
+
+    /// Retourne les règles équivalentes par réflexion et conjugaison (sans doublon),
+    /// triées par numéro croissant.
+    pub fn equivalents(&self) -> Vec<RuleInfo> {
+        let mut equivalents = vec![
+            self.clone(),
+            self.reflected(),
+            self.complemented(),
+            self.reflected_complemented(),
+        ];
+        equivalents.sort_by_key(RuleInfo::number);
+        equivalents.dedup();
+        equivalents
+    }
+
+    /// Retourne le représentant de la classe d'équivalence : la règle équivalente de plus
+    /// petit numéro. Les 256 règles élémentaires forment ainsi 88 classes.
+    pub fn representative(&self) -> RuleInfo {
+        self.equivalents().swap_remove(0)
+    }
+
+    /// Retourne `true` si la règle est additive : le nouvel état est une combinaison
+    /// linéaire des états du voisinage modulo `k` (règles 60, 90, 102, 150, ...).
+    pub fn is_additive(&self) -> bool {
+        let k = self.colours as usize;
+        let coefficients: Vec<usize> = (0..self.window)
+            .map(|position| {
+                let mut unit = vec![0; self.window];
+                unit[position] = 1;
+                self.table[self.index(&unit)] as usize
+            })
+            .collect();
+
+        (0..self.table.len()).all(|index| {
+            let neighbourhood = self.neighbourhood(index);
+            let linear: usize = neighbourhood
+                .iter()
+                .zip(&coefficients)
+                .map(|(&s, &a)| s as usize * a)
+                .sum();
+            self.table[index] as usize == linear % k
+        })
+    }
+
+    /// Retourne `true` si, pour tout choix des autres cellules, le nouvel état est une
+    /// bijection de la cellule la plus à gauche du voisinage (règle 30, par exemple).
+    pub fn is_left_permutive(&self) -> bool {
+        self.is_permutive_at(0)
+    }
+
+    /// Retourne `true` si, pour tout choix des autres cellules, le nouvel état est une
+    /// bijection de la cellule la plus à droite du voisinage (règle 86, par exemple).
+    pub fn is_right_permutive(&self) -> bool {
+        self.is_permutive_at(self.window - 1)
+    }
+
+    /// Construit une `WolframRule` équivalente.
+    pub fn to_rule(&self) -> WolframRule {
+        WolframRule::with_colours(&self.number(), self.colours, self.window)
+    }
+
+    fn is_permutive_at(&self, position: usize) -> bool {
+        let k = self.colours as usize;
+        (0..self.table.len())
+            .filter(|&index| self.neighbourhood(index)[position] == 0)
+            .all(|index| {
+                let mut seen = vec![false; k];
+                let mut neighbourhood = self.neighbourhood(index);
+                for s in 0..k {
+                    neighbourhood[position] = s as u8;
+                    seen[self.table[self.index(&neighbourhood)] as usize] = true;
+                }
+                seen.iter().all(|&b| b)
+            })
+    }
+
+    fn neighbourhood(&self, index: usize) -> Vec<u8> {
+        let k = self.colours as usize;
+        let mut rest = index;
+        let mut neighbourhood = vec![0; self.window];
+        for cell in neighbourhood.iter_mut().rev() {
+            *cell = (rest % k) as u8;
+            rest /= k;
+        }
+        neighbourhood
+    }
+
+    fn index(&self, neighbourhood: &[u8]) -> usize {
+        let k = self.colours as usize;
+        neighbourhood.iter().fold(0, |index, &s| index * k + s as usize)
+    }
+
+    fn with_table(&self, table: Vec<u8>) -> RuleInfo {
+        Self { table, window: self.window, colours: self.colours }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{by_name, Rule30, TotalisticRule};
+
+    fn numbers(infos: &[RuleInfo]) -> Vec<u8> {
+        infos.iter().map(|info| info.elementary_number().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_rule_30_symmetries() {
+        let info = Rule30.info().unwrap();
+        assert_eq!(info.elementary_number(), Some(30));
+        assert_eq!(info.table(), &[0, 1, 1, 1, 1, 0, 0, 0]);
+        assert_eq!(info.reflected().elementary_number(), Some(86));
+        assert_eq!(info.complemented().elementary_number(), Some(135));
+        assert_eq!(info.reflected_complemented().elementary_number(), Some(149));
+        assert_eq!(numbers(&info.equivalents()), vec![30, 86, 135, 149]);
+        assert_eq!(info.representative().elementary_number(), Some(30));
+        assert!(info.is_left_permutive());
+        assert!(!info.is_right_permutive());
+        assert!(!info.is_additive());
+
+        assert_eq!(numbers(&RuleInfo::elementary(110).equivalents()), vec![110, 124, 137, 193]);
+        assert_eq!(RuleInfo::elementary(184).representative().elementary_number(), Some(184));
+        assert_eq!(numbers(&RuleInfo::elementary(90).equivalents()), vec![90, 165]);
+    }
+
+    #[test]
+    fn test_88_classes() {
+        let mut representatives: Vec<u8> = (0..=255)
+            .map(|n| RuleInfo::elementary(n).representative().elementary_number().unwrap())
+            .collect();
+        representatives.sort();
+        representatives.dedup();
+        assert_eq!(representatives.len(), 88);
+    }
+
+    #[test]
+    fn test_additive_and_permutive() {
+        let additive: Vec<u8> = (0..=255).filter(|&n| RuleInfo::elementary(n).is_additive()).collect();
+        assert_eq!(additive, vec![0, 60, 90, 102, 150, 170, 204, 240]);
+
+        let both: Vec<u8> = (0..=255)
+            .filter(|&n| RuleInfo::elementary(n).is_left_permutive() && RuleInfo::elementary(n).is_right_permutive())
+            .collect();
+        assert_eq!(both, vec![90, 105, 150, 165]);
+
+        let sum_mod_3 = TotalisticRule::new(&Natural::from_digits(&[0, 1, 2, 0, 1, 2, 0], 3), 3, 3);
+        assert!(RuleInfo::of(&sum_mod_3).unwrap().is_additive());
+    }
+
+    #[test]
+    fn test_registry() {
+        for (name, number) in [("rule30", 30u8), ("rule110", 110), ("Rule 184", 184), ("sierpinski", 90), ("traffic", 184)] {
+            let rule = by_name(name).unwrap();
+            assert_eq!(rule.info().unwrap().elementary_number(), Some(number), "{}", name);
+        }
+        assert!(by_name("rule256").is_none());
+        assert!(by_name("life").is_none());
+        for malformed in ["rule+30", "rule-30", "rule", "rule 3x", "rule0x1e"] {
+            assert!(by_name(malformed).is_none(), "{}", malformed);
+        }
+    }
+}
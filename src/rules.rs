@@ -1,41 +1,431 @@
+use crate::natural::Natural;
+use crate::rule_info::RuleInfo;
+
+/// Retourne le numéro de Wolfram d'une règle élémentaire binaire.
+///
+/// La règle est sondée sur les 8 voisinages binaires. Si la règle n'est pas déterministe,
+/// pas de rayon 1 ou si l'une des sorties n'est pas un état binaire, la règle n'est pas
+/// élémentaire et `None` est retourné.
+pub fn elementary_number(rule: &dyn Rule) -> Option<u8> {
+    if rule.window() != 3 || !rule.is_deterministic() {
+        return None;
+    }
+
+    let mut number = 0u8;
+    for index in 0..8u8 {
+        let state = rule.apply(&[(index >> 2) & 1, (index >> 1) & 1, index & 1]);
+        if state > 1 {
+            return None;
+        }
+        number |= state << index;
+    }
+
+    Some(number)
+}
+
+/// Règle locale d'un automate cellulaire unidimensionnel.
+///
+/// Le voisinage d'une cellule est une fenêtre de `window()` cellules consécutives,
+/// soit un rayon `r = (window() - 1) / 2` : 3 pour le rayon 1, 5 pour le rayon 2,
+/// 2 pour le rayon 1/2, 4 pour le rayon 3/2... Pour une fenêtre de taille paire,
+/// la fenêtre déborde d'une cellule de plus à gauche qu'à droite.
 pub trait Rule {
-    fn apply(&self, left: u8, center: u8, right: u8) -> u8;
+    /// Calcule le nouvel état d'une cellule à partir de son voisinage,
+    /// donné de la gauche vers la droite.
+    fn apply(&self, neighbourhood: &[u8]) -> u8;
+
+    /// Retourne le nombre de cellules du voisinage.
+    fn window(&self) -> usize {
+        3
+    }
+
+    /// Retourne le nombre de voisines situées à gauche de la cellule.
+    fn left_reach(&self) -> usize {
+        self.window() / 2
+    }
+
+    /// Retourne le nombre de voisines situées à droite de la cellule.
+    fn right_reach(&self) -> usize {
+        (self.window() - 1) / 2
+    }
+
+    /// Retourne le nombre d'états `k` d'une cellule (de `0` à `k - 1`).
+    fn colours(&self) -> u8 {
+        2
+    }
+
+    /// Retourne `false` si la règle tire ses résultats au hasard : elle ne peut alors
+    /// ni être sondée ni être remplacée par une table.
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+
+    /// Retourne les métadonnées de la règle (table, symétries, additivité...),
+    /// ou `None` si la règle n'est pas déterministe.
+    fn info(&self) -> Option<RuleInfo> {
+        RuleInfo::of(self)
+    }
+
+    /// Retourne la table de transition, indexée comme le numéro de Wolfram.
+    fn lookup_table(&self) -> Option<Vec<u8>> {
+        self.info().map(|info| info.table().to_vec())
+    }
 
-    fn states(&self) -> u64;
+    /// Retourne le numéro de Wolfram de la règle.
+    fn number(&self) -> Option<Natural> {
+        self.info().map(|info| info.number())
+    }
 }
 
 pub struct Rule30;
 
 impl Rule for Rule30 {
-    fn apply(&self, left: u8, center: u8, right: u8) -> u8 {
-        left ^ (center | right)
-    }
-
-    fn states(&self) -> u64 {
-        todo!()
+    fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        neighbourhood[0] ^ (neighbourhood[1] | neighbourhood[2])
     }
 }
 
+/// Règle définie par son numéro de Wolfram.
+///
+/// Le voisinage, lu de gauche à droite, est interprété comme un entier en base `k`
+/// (la cellule la plus à gauche est le chiffre de poids fort) ; le nouvel état est le
+/// chiffre correspondant du numéro de la règle écrit en base `k`.
 pub struct WolframRule {
-    rule_number: u8,
+    table: Vec<u8>,
+    window: usize,
+    colours: u8,
 }
 
+/// Nombre maximal d'entrées de la table d'une `WolframRule` (`k^window`).
+const MAX_TABLE_SIZE: usize = 1 << 20;
+
 impl WolframRule {
+    /// Crée une règle élémentaire (rayon 1) à partir de son numéro.
     pub fn new(rule_number: u8) -> Self {
-        Self { rule_number }
+        Self::with_window(rule_number as u128, 3)
+    }
+
+    /// Crée une règle binaire sur un voisinage de `window` cellules.
+    ///
+    /// Le numéro est compris entre `0` et `2^(2^window) - 1`, ce qui limite la fenêtre
+    /// à 7 cellules (rayon 3) ; au-delà, voir [`WolframRule::with_colours`].
+    ///
+    /// # Exemple
+    /// ```text
+    /// let rule = WolframRule::with_window(6, 2); // rayon 1/2 : XOR des deux cellules
+    /// let rule = WolframRule::with_window(0xFFFF_FFFE, 5); // rayon 2 : OU des cinq cellules
+    /// ```
+    pub fn with_window(rule_number: u128, window: usize) -> Self {
+        assert!((1..=7).contains(&window), "WolframRule: window should be between 1 and 7");
+        Self::with_colours(&Natural::from(rule_number), 2, window)
+    }
+
+    /// Crée une règle à `colours` états sur un voisinage de `window` cellules.
+    ///
+    /// Le numéro est compris entre `0` et `k^(k^window) - 1` avec `k = colours`.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let number: Natural = "7625597484986".parse().unwrap(); // 3^27 - 1
+    /// let rule = WolframRule::with_colours(&number, 3, 3); // tout voisinage donne l'état 2
+    /// ```
+    pub fn with_colours(rule_number: &Natural, colours: u8, window: usize) -> Self {
+        assert!(colours >= 2, "WolframRule: at least two colours are needed");
+        let size = (colours as usize)
+            .checked_pow(window as u32)
+            .filter(|&size| size <= MAX_TABLE_SIZE)
+            .expect("WolframRule: neighbourhood table too large");
+        let table = rule_number
+            .digits(colours, size)
+            .expect("WolframRule: rule number too large for this window");
+
+        Self { table, window, colours }
     }
 }
 
 impl Rule for WolframRule {
+    fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        let k = self.colours as usize;
+        let index = neighbourhood.iter().fold(0, |index, &state| index * k + state as usize);
+        self.table[index]
+    }
+
+    fn window(&self) -> usize {
+        self.window
+    }
+
+    fn colours(&self) -> u8 {
+        self.colours
+    }
+
+    fn lookup_table(&self) -> Option<Vec<u8>> {
+        Some(self.table.clone())
+    }
+}
+
+/// Règle totalistique : le nouvel état ne dépend que de la somme des états du voisinage.
+///
+/// La somme `σ` va de `0` à `(k - 1) * window` ; le nouvel état est le chiffre d'indice `σ`
+/// du code de la règle écrit en base `k`.
+pub struct TotalisticRule {
+    table: Vec<u8>,
+    window: usize,
+    colours: u8,
+}
+
+impl TotalisticRule {
+    /// Crée une règle totalistique à `colours` états à partir de son code de Wolfram.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let rule = TotalisticRule::new(&Natural::from(1599u64), 3, 3); // code 1599, k = 3, r = 1
+    /// ```
+    pub fn new(code: &Natural, colours: u8, window: usize) -> Self {
+        assert!(colours >= 2, "TotalisticRule: at least two colours are needed");
+        let size = (colours as usize - 1) * window + 1;
+        let table = code
+            .digits(colours, size)
+            .expect("TotalisticRule: code too large for this window");
+
+        Self { table, window, colours }
+    }
+
+    /// Retourne le code de Wolfram de la règle.
+    pub fn code(&self) -> Natural {
+        Natural::from_digits(&self.table, self.colours)
+    }
+}
+
+impl Rule for TotalisticRule {
+    fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        let sum: usize = neighbourhood.iter().map(|&state| state as usize).sum();
+        self.table[sum]
+    }
 
-    fn apply(&self, left: u8, center: u8, right: u8) -> u8 {
-        let index = (left << 2) | (center << 1) | right;
-        let bit = (self.rule_number >> index) & 1;
+    fn window(&self) -> usize {
+        self.window
+    }
+
+    fn colours(&self) -> u8 {
+        self.colours
+    }
+}
+
+/// Règle totalistique externe : le nouvel état dépend de l'état de la cellule
+/// et de la somme des états de ses voisines.
+///
+/// Avec `c` l'état de la cellule et `σ` la somme des autres cellules du voisinage, le
+/// nouvel état est le chiffre d'indice `k * σ + c` du code écrit en base `k`
+/// (la convention de Wolfram, où le Jeu de la vie a le code 224).
+pub struct OuterTotalisticRule {
+    table: Vec<u8>,
+    window: usize,
+    colours: u8,
+}
+
+impl OuterTotalisticRule {
+    /// Crée une règle totalistique externe à `colours` états à partir de son code de Wolfram.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let rule = OuterTotalisticRule::new(&Natural::from(12u64), 2, 3); // règle 90
+    /// ```
+    pub fn new(code: &Natural, colours: u8, window: usize) -> Self {
+        assert!(colours >= 2, "OuterTotalisticRule: at least two colours are needed");
+        assert!(window >= 1, "OuterTotalisticRule: window should contain the cell");
+        let k = colours as usize;
+        let size = k * ((k - 1) * (window - 1) + 1);
+        let table = code
+            .digits(colours, size)
+            .expect("OuterTotalisticRule: code too large for this window");
+
+        Self { table, window, colours }
+    }
+
+    /// Retourne le code de Wolfram de la règle.
+    pub fn code(&self) -> Natural {
+        Natural::from_digits(&self.table, self.colours)
+    }
+}
+
+impl Rule for OuterTotalisticRule {
+    fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        let centre = neighbourhood[self.left_reach()] as usize;
+        let sum: usize = neighbourhood.iter().map(|&state| state as usize).sum();
+        self.table[self.colours as usize * (sum - centre) + centre]
+    }
+
+    fn window(&self) -> usize {
+        self.window
+    }
+
+    fn colours(&self) -> u8 {
+        self.colours
+    }
+}
+
+/// Noms usuels de quelques règles élémentaires, reconnus par [`by_name`].
+const ALIASES: [(&str, u8); 5] = [
+    ("sierpinski", 90),
+    ("fredkin", 150),
+    ("traffic", 184),
+    ("majority", 232),
+    ("identity", 204),
+];
+
+/// Retourne la règle élémentaire correspondant à un nom : `"rule30"`, `"Rule 110"`,
+/// `"rule184"`... ou un nom usuel comme `"traffic"` (règle 184) ou `"sierpinski"` (règle 90).
+///
+/// La casse et les espaces sont ignorés ; `None` est retourné pour un nom inconnu.
+pub fn by_name(name: &str) -> Option<WolframRule> {
+    let name: String = name.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    let number = match name.strip_prefix("rule") {
+        // `parse` accepterait un signe `+` en tête : seuls des chiffres sont admis.
+        Some(number) if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) => number.parse::<u8>().ok()?,
+        Some(_) => return None,
+        None => ALIASES.iter().find(|(alias, _)| *alias == name)?.1,
+    };
+    Some(WolframRule::new(number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::row::Row;
+
+    fn row_from_binary(binary: &str) -> Row {
+        Row::new(binary.chars().map(|c| Cell::new(c.to_digit(10).unwrap() as u8)).collect())
+    }
+
+    fn to_binary(row: &Row) -> String {
+        (0..row.len()).map(|i| row.get(i).unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn test_radius_one_unchanged() {
+        // Le bit `l << 2 | c << 1 | r` du numéro donne l'image du voisinage `(l, c, r)`.
+        let row = row_from_binary("0001001110100101100010");
+        let n = row.len();
+        let state = |i: usize| row.get(i % n).unwrap().state() as u32;
+        for number in 0..=255u8 {
+            let expected: String = (0..n)
+                .map(|i| (number as u32 >> (state(i + n - 1) << 2 | state(i) << 1 | state(i + 1)) & 1).to_string())
+                .collect();
+            assert_eq!(to_binary(&row.next(&WolframRule::new(number))), expected, "rule {}", number);
+            assert_eq!(to_binary(&row.next(&WolframRule::with_window(number as u128, 3))), expected, "rule {}", number);
+        }
+
+        let seed = row_from_binary("0001000");
+        assert_eq!(to_binary(&seed.next(&WolframRule::new(30))), "0011100");
+        assert_eq!(to_binary(&seed.next(&WolframRule::new(90))), "0010100");
+        assert_eq!(to_binary(&seed.next(&WolframRule::new(110))), "0011000");
+        assert_eq!(to_binary(&row.next(&Rule30)), to_binary(&row.next(&WolframRule::new(30))));
+        assert_eq!(elementary_number(&Rule30), Some(30));
+    }
+
+    #[test]
+    fn test_half_radius() {
+        let rule = WolframRule::with_window(6, 2);
+        assert_eq!((rule.left_reach(), rule.right_reach()), (1, 0));
+        assert_eq!(to_binary(&row_from_binary("0100").next(&rule)), "0110");
+        assert_eq!(elementary_number(&rule), None);
+    }
+
+    #[test]
+    fn test_radius_two_majority() {
+        let number = (0..32u32)
+            .filter(|index| index.count_ones() >= 3)
+            .fold(0u128, |number, index| number | (1 << index));
+        let rule = WolframRule::with_window(number, 5);
+        assert_eq!((rule.left_reach(), rule.right_reach()), (2, 2));
+
+        let row = row_from_binary("1101000111010");
+        let n = row.len();
+        let expected: String = (0..n)
+            .map(|i| {
+                let ones = (0..5)
+                    .filter(|d| row.get((i + n + d - 2) % n).unwrap().state() == 1)
+                    .count();
+                if ones >= 3 { '1' } else { '0' }
+            })
+            .collect();
+        assert_eq!(to_binary(&row.next(&rule)), expected);
+    }
+
+    #[test]
+    fn test_radius_three_number() {
+        let rule = WolframRule::with_window(1u128 << 127, 7);
+        assert_eq!(rule.apply(&[1; 7]), 1);
+        assert_eq!(rule.apply(&[1, 1, 1, 1, 1, 1, 0]), 0);
+    }
+
+    #[test]
+    fn test_three_colours() {
+        // Nouvel état : somme du voisinage modulo 3.
+        let digits: Vec<u8> = (0..27u32).map(|i| ((i / 9 + i / 3 % 3 + i % 3) % 3) as u8).collect();
+        let number = Natural::from_digits(&digits, 3);
+        let rule = WolframRule::with_colours(&number, 3, 3);
+        assert_eq!(rule.number(), Some(number));
+        assert_eq!(rule.colours(), 3);
+        assert_eq!(rule.apply(&[2, 2, 1]), 2);
+        assert_eq!(rule.apply(&[1, 0, 1]), 2);
+        assert_eq!(rule.apply(&[2, 0, 1]), 0);
+
+        let row = Row::new([0, 1, 2, 0].iter().map(|&s| Cell::new(s)).collect());
+        assert_eq!(row.next(&rule).to_string(), "#..+");
+    }
+
+    #[test]
+    fn test_colours_beyond_u64() {
+        let number: Natural = "340282366920938463463374607431768211455".parse().unwrap();
+        let rule = WolframRule::with_colours(&number, 4, 3);
+        assert_eq!(rule.apply(&[0, 1, 2]), 3);
+        assert_eq!(rule.number().unwrap().to_string(), "340282366920938463463374607431768211455");
+        assert_eq!(elementary_number(&rule), None);
+    }
 
-        bit
+    #[test]
+    fn test_totalistic_matches_elementary() {
+        // Code 10 = 0b1010 : somme 1 ou 3, soit la règle 150.
+        let totalistic = TotalisticRule::new(&Natural::from(10u64), 2, 3);
+        // Code 12 : somme externe égale à 1, soit la règle 90.
+        let outer = OuterTotalisticRule::new(&Natural::from(12u64), 2, 3);
+        assert_eq!(elementary_number(&totalistic), Some(150));
+        assert_eq!(elementary_number(&outer), Some(90));
+        assert_eq!(totalistic.code(), Natural::from(10u64));
     }
 
-    fn states(&self) -> u64 {
-        self.rule_number as u64
+    #[test]
+    fn test_totalistic_three_colours() {
+        // 1599 = 2012020 en base 3 : les sommes 0 à 6 donnent 0, 2, 0, 2, 1, 0, 2.
+        let rule = TotalisticRule::new(&Natural::from(1599u64), 3, 3);
+        let expected = [0, 2, 0, 2, 1, 0, 2];
+        for index in 0..27u8 {
+            let neighbourhood = [index / 9, index / 3 % 3, index % 3];
+            let sum: u8 = neighbourhood.iter().sum();
+            assert_eq!(rule.apply(&neighbourhood), expected[sum as usize]);
+        }
+
+        let general = WolframRule::with_colours(&wolfram_number(&rule), 3, 3);
+        let row = Row::new([0, 0, 0, 1, 0, 0, 0].iter().map(|&s| Cell::new(s)).collect());
+        assert_eq!(row.next(&rule).to_string(), row.next(&general).to_string());
+        assert_eq!(row.next(&rule).to_string(), "..+++..");
+    }
+
+    #[test]
+    fn test_outer_totalistic_radius_two() {
+        let k = 3;
+        let code = Natural::from(123_456_789u64);
+        let rule = OuterTotalisticRule::new(&code, k, 5);
+        let digits = code.digits(k, 27).unwrap();
+        let neighbourhood = [2, 1, 2, 0, 1];
+        assert_eq!(rule.apply(&neighbourhood), digits[3 * 4 + 2]);
     }
-}
\ No newline at end of file
+
+    fn wolfram_number(rule: &dyn Rule) -> Natural {
+        let k = rule.colours();
+        let table: Vec<u8> = (0..27u8).map(|i| rule.apply(&[i / 9, i / 3 % 3, i % 3])).collect();
+        Natural::from_digits(&table, k)
+    }
+}
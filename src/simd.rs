@@ -0,0 +1,175 @@
+use std::sync::OnceLock;
+use crate::bit_row::eval_word;
+
+/// Noyau de calcul utilisé pour faire évoluer une ligne compactée.
+///
+/// Tous les noyaux donnent exactement les mêmes mots ; ils ne diffèrent que par
+/// le nombre de mots traités à la fois (1, 2 ou 4).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kernel {
+    /// Un mot `u64` à la fois, sur toute plateforme.
+    Scalar,
+    /// Deux mots à la fois, avec les registres SSE2 de 128 bits (x86-64).
+    Sse2,
+    /// Quatre mots à la fois, avec les registres AVX2 de 256 bits (x86-64).
+    Avx2,
+}
+
+impl Kernel {
+    /// Retourne le noyau le plus rapide disponible sur le processeur courant.
+    ///
+    /// La détection a lieu à l'exécution : un même binaire utilise AVX2 sur un processeur
+    /// qui le permet, et se replie sur SSE2 ou sur le noyau scalaire sinon. Le processeur
+    /// n'est interrogé qu'au premier appel.
+    pub fn detect() -> Kernel {
+        static DETECTED: OnceLock<Kernel> = OnceLock::new();
+        *DETECTED.get_or_init(|| {
+            [Kernel::Avx2, Kernel::Sse2].into_iter().find(Kernel::is_available).unwrap_or(Kernel::Scalar)
+        })
+    }
+
+    /// Retourne `true` si le noyau peut être utilisé sur le processeur courant.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Kernel::Scalar => true,
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Sse2 => is_x86_feature_detected!("sse2"),
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Avx2 => is_x86_feature_detected!("avx2"),
+            #[cfg(not(target_arch = "x86_64"))]
+            _ => false,
+        }
+    }
+
+    /// Calcule dans `out` les mots de la ligne suivante.
+    ///
+    /// `carry_left` est la cellule fantôme de gauche (bit 0) et `carry_right` la cellule fantôme
+    /// de droite, déjà placée au bit qui suit la dernière cellule du dernier mot.
+    ///
+    /// La disponibilité du noyau est vérifiée par l'appelant, une fois, au moment où il est choisi.
+    pub(crate) fn step(&self, words: &[u64], out: &mut [u64], masks: &[u64; 8], carry_left: u64, carry_right: u64) {
+        debug_assert!(self.is_available(), "Kernel: {:?} is not supported by this processor", self);
+        debug_assert_eq!(words.len(), out.len());
+        match self {
+            Kernel::Scalar => {
+                for (j, word) in out.iter_mut().enumerate() {
+                    *word = scalar_word(words, j, masks, carry_left, carry_right);
+                }
+            }
+            #[cfg(target_arch = "x86_64")]
+            // SAFETY : l'appelant a vérifié la disponibilité de SSE2.
+            Kernel::Sse2 => unsafe { x86::step_sse2(words, out, masks, carry_left, carry_right) },
+            #[cfg(target_arch = "x86_64")]
+            // SAFETY : l'appelant a vérifié la disponibilité d'AVX2.
+            Kernel::Avx2 => unsafe { x86::step_avx2(words, out, masks, carry_left, carry_right) },
+            #[cfg(not(target_arch = "x86_64"))]
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Calcule le mot `j` de la ligne suivante.
+#[inline(always)]
+fn scalar_word(words: &[u64], j: usize, masks: &[u64; 8], carry_left: u64, carry_right: u64) -> u64 {
+    let last = words.len() - 1;
+    let c = words[j];
+    let from_left = if j == 0 { carry_left } else { words[j - 1] >> 63 };
+    let from_right = if j == last { carry_right } else { words[j + 1] << 63 };
+    eval_word(masks, (c << 1) | from_left, c, (c >> 1) | from_right)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+    use super::scalar_word;
+
+    /// Le premier et le dernier mot, qui reçoivent les cellules fantômes, sont calculés
+    /// par le noyau scalaire ; les mots intérieurs par paquets de quatre.
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn step_avx2(words: &[u64], out: &mut [u64], masks: &[u64; 8], carry_left: u64, carry_right: u64) {
+        let n = words.len();
+        let m: [__m256i; 8] = masks.map(|mask| _mm256_set1_epi64x(mask as i64));
+        let mux = |s: __m256i, one: __m256i, zero: __m256i| _mm256_or_si256(_mm256_and_si256(s, one), _mm256_andnot_si256(s, zero));
+
+        out[0] = scalar_word(words, 0, masks, carry_left, carry_right);
+        let mut j = 1;
+        while j + 4 < n {
+            // SAFETY : les mots j - 1 à j + 4 existent, et les mots j à j + 3 de `out` aussi.
+            unsafe {
+                let p = _mm256_loadu_si256(words.as_ptr().add(j - 1) as *const __m256i);
+                let c = _mm256_loadu_si256(words.as_ptr().add(j) as *const __m256i);
+                let q = _mm256_loadu_si256(words.as_ptr().add(j + 1) as *const __m256i);
+
+                let l = _mm256_or_si256(_mm256_slli_epi64::<1>(c), _mm256_srli_epi64::<63>(p));
+                let r = _mm256_or_si256(_mm256_srli_epi64::<1>(c), _mm256_slli_epi64::<63>(q));
+
+                let h0 = mux(c, mux(r, m[3], m[2]), mux(r, m[1], m[0]));
+                let h1 = mux(c, mux(r, m[7], m[6]), mux(r, m[5], m[4]));
+                _mm256_storeu_si256(out.as_mut_ptr().add(j) as *mut __m256i, mux(l, h1, h0));
+            }
+            j += 4;
+        }
+        for (k, word) in out.iter_mut().enumerate().skip(j.min(n)) {
+            *word = scalar_word(words, k, masks, carry_left, carry_right);
+        }
+    }
+
+    /// Même découpage que [`step_avx2`], par paquets de deux mots.
+    #[target_feature(enable = "sse2")]
+    pub(super) unsafe fn step_sse2(words: &[u64], out: &mut [u64], masks: &[u64; 8], carry_left: u64, carry_right: u64) {
+        let n = words.len();
+        let m: [__m128i; 8] = masks.map(|mask| _mm_set1_epi64x(mask as i64));
+        let mux = |s: __m128i, one: __m128i, zero: __m128i| _mm_or_si128(_mm_and_si128(s, one), _mm_andnot_si128(s, zero));
+
+        out[0] = scalar_word(words, 0, masks, carry_left, carry_right);
+        let mut j = 1;
+        while j + 2 < n {
+            // SAFETY : les mots j - 1 à j + 2 existent, et les mots j et j + 1 de `out` aussi.
+            unsafe {
+                let p = _mm_loadu_si128(words.as_ptr().add(j - 1) as *const __m128i);
+                let c = _mm_loadu_si128(words.as_ptr().add(j) as *const __m128i);
+                let q = _mm_loadu_si128(words.as_ptr().add(j + 1) as *const __m128i);
+
+                let l = _mm_or_si128(_mm_slli_epi64::<1>(c), _mm_srli_epi64::<63>(p));
+                let r = _mm_or_si128(_mm_srli_epi64::<1>(c), _mm_slli_epi64::<63>(q));
+
+                let h0 = mux(c, mux(r, m[3], m[2]), mux(r, m[1], m[0]));
+                let h1 = mux(c, mux(r, m[7], m[6]), mux(r, m[5], m[4]));
+                _mm_storeu_si128(out.as_mut_ptr().add(j) as *mut __m128i, mux(l, h1, h0));
+            }
+            j += 2;
+        }
+        for (k, word) in out.iter_mut().enumerate().skip(j.min(n)) {
+            *word = scalar_word(words, k, masks, carry_left, carry_right);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bit_row::BitRow;
+    use crate::random::Random;
+
+    #[test]
+    fn test_kernels_match_scalar() {
+        let mut random = Random::new(2024);
+        let kernels: Vec<Kernel> = [Kernel::Sse2, Kernel::Avx2].into_iter().filter(Kernel::is_available).collect();
+        assert!(Kernel::detect().is_available());
+
+        for len in [1, 64, 65, 127, 128, 129, 191, 256, 320, 383, 640, 1000] {
+            let mut row = BitRow::new(len);
+            for i in 0..len {
+                row.set(i, random.below(2) as u8);
+            }
+            for rule_number in 0..=255u8 {
+                for (left, right) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let expected = row.next_with(Kernel::Scalar, rule_number, left, right);
+                    for &kernel in &kernels {
+                        assert_eq!(row.next_with(kernel, rule_number, left, right), expected, "{:?}, rule {} on {} cells", kernel, rule_number, len);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::io::{self, Write};
+use crate::automaton::Automaton;
+use crate::palette::Palette;
+use crate::row::Row;
+
+/// Réponse d'un observateur après avoir reçu une ligne.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    /// L'observateur demande l'arrêt de l'évolution.
+    Stop,
+}
+
+/// Observateur qui reçoit chaque ligne d'un automate au moment où elle est calculée.
+pub trait RowSink {
+    /// Reçoit la ligne de la génération donnée.
+    fn observe(&mut self, generation: usize, row: &Row) -> Flow;
+}
+
+/// Bilan d'une évolution menée par un [`Pipeline`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunStatus {
+    /// Toutes les itérations demandées ont été calculées.
+    Completed { steps: u64 },
+    /// L'observateur d'indice `sink` a demandé l'arrêt après `steps` itérations.
+    Stopped { steps: u64, sink: usize },
+}
+
+/// Ensemble d'observateurs alimentés côte à côte pendant l'évolution d'un automate.
+///
+/// Associé à [`Retention::Nothing`](crate::retention::Retention::Nothing), il permet de traiter
+/// de très longues évolutions sans conserver les lignes en mémoire.
+///
+/// # Exemple
+/// ```text
+/// let mut statistics = StatisticsSink::default();
+/// let mut writer = WriterSink::new(File::create("rule30.txt")?);
+/// let status = Pipeline::new()
+///     .add(&mut statistics)
+///     .add(&mut writer)
+///     .run(&mut automaton, 10_000);
+/// ```
+#[derive(Default)]
+pub struct Pipeline<'a> {
+    sinks: Vec<&'a mut dyn RowSink>,
+    /// Dernière génération transmise aux observateurs.
+    last: Option<usize>,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute un observateur ; les observateurs reçoivent chaque ligne dans leur ordre d'ajout.
+    pub fn add(&mut self, sink: &'a mut dyn RowSink) -> &mut Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Fait évoluer l'automate d'au plus `steps` itérations en transmettant chaque nouvelle ligne
+    /// aux observateurs.
+    ///
+    /// La ligne courante est transmise d'abord, si elle ne l'a pas déjà été. Tous les observateurs
+    /// reçoivent chaque ligne ; l'évolution s'arrête après la première ligne pour laquelle l'un
+    /// d'eux retourne [`Flow::Stop`].
+    pub fn run(&mut self, automaton: &mut Automaton, steps: u64) -> RunStatus {
+        if let Some(sink) = self.feed(automaton) {
+            return RunStatus::Stopped { steps: 0, sink };
+        }
+
+        for step in 1..=steps {
+            automaton.next();
+            if let Some(sink) = self.feed(automaton) {
+                return RunStatus::Stopped { steps: step, sink };
+            }
+        }
+
+        RunStatus::Completed { steps }
+    }
+
+    /// Transmet la ligne courante aux observateurs et retourne l'indice du premier
+    /// observateur qui demande l'arrêt.
+    fn feed(&mut self, automaton: &Automaton) -> Option<usize> {
+        let generation = automaton.iteration() - 1;
+        if self.last == Some(generation) {
+            return None;
+        }
+        self.last = Some(generation);
+
+        let row = automaton.current();
+        let mut stop = None;
+        for (index, sink) in self.sinks.iter_mut().enumerate() {
+            if sink.observe(generation, row) == Flow::Stop && stop.is_none() {
+                stop = Some(index);
+            }
+        }
+        stop
+    }
+}
+
+/// Écrit chaque ligne, suivie d'un retour à la ligne, dans un fichier, un terminal...
+///
+/// Sans palette, les états sont écrits avec leurs symboles par défaut ; avec une palette,
+/// la ligne est rendue selon ses symboles et ses couleurs. Une erreur d'écriture arrête l'évolution.
+pub struct WriterSink<W: Write> {
+    writer: W,
+    palette: Option<Palette>,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> WriterSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, palette: None, error: None }
+    }
+
+    /// Rend les lignes selon une palette.
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    /// Retourne l'erreur d'écriture qui a arrêté l'évolution, s'il y en a une.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Retourne l'écrivain sous-jacent.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> RowSink for WriterSink<W> {
+    fn observe(&mut self, _generation: usize, row: &Row) -> Flow {
+        let line = match &self.palette {
+            Some(palette) => row.to_string_with(palette),
+            None => row.to_string(),
+        };
+        match writeln!(self.writer, "{}", line) {
+            Ok(()) => Flow::Continue,
+            Err(error) => {
+                self.error = Some(error);
+                Flow::Stop
+            }
+        }
+    }
+}
+
+/// Accumule des statistiques sur les lignes reçues.
+#[derive(Clone, Debug, Default)]
+pub struct StatisticsSink {
+    rows: usize,
+    /// Nombre total de cellules reçues dans chaque état.
+    counts: Vec<u64>,
+    /// Proportion de cellules dans un état non nul, ligne par ligne.
+    densities: Vec<f64>,
+}
+
+impl StatisticsSink {
+    /// Retourne le nombre de lignes reçues.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Retourne le nombre total de cellules reçues dans chaque état, indexé par l'état.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Retourne, pour chaque ligne reçue, la proportion de cellules dans un état non nul.
+    pub fn densities(&self) -> &[f64] {
+        &self.densities
+    }
+
+    /// Retourne la densité moyenne des lignes reçues, ou `None` si aucune ligne n'a été reçue.
+    pub fn mean_density(&self) -> Option<f64> {
+        if self.densities.is_empty() {
+            return None;
+        }
+        Some(self.densities.iter().sum::<f64>() / self.densities.len() as f64)
+    }
+}
+
+impl RowSink for StatisticsSink {
+    fn observe(&mut self, _generation: usize, row: &Row) -> Flow {
+        let mut alive = 0;
+        for i in 0..row.len() {
+            let state = row.get(i).unwrap().state() as usize;
+            if state >= self.counts.len() {
+                self.counts.resize(state + 1, 0);
+            }
+            self.counts[state] += 1;
+            alive += (state != 0) as usize;
+        }
+
+        self.rows += 1;
+        self.densities.push(if row.len() == 0 { 0.0 } else { alive as f64 / row.len() as f64 });
+        Flow::Continue
+    }
+}
+
+/// Condition d'arrêt : demande l'arrêt dès qu'une ligne vérifie le prédicat.
+///
+/// # Exemple
+/// ```text
+/// // Arrêt quand toutes les cellules sont mortes.
+/// let mut extinct = StopWhen::new(|_, row: &Row| (0..row.len()).all(|i| row.get(i).unwrap().state() == 0));
+/// ```
+pub struct StopWhen<F: FnMut(usize, &Row) -> bool> {
+    predicate: F,
+}
+
+impl<F: FnMut(usize, &Row) -> bool> StopWhen<F> {
+    pub fn new(predicate: F) -> Self {
+        Self { predicate }
+    }
+}
+
+impl<F: FnMut(usize, &Row) -> bool> RowSink for StopWhen<F> {
+    fn observe(&mut self, generation: usize, row: &Row) -> Flow {
+        if (self.predicate)(generation, row) { Flow::Stop } else { Flow::Continue }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+    use crate::retention::Retention;
+    use crate::rules::WolframRule;
+
+    fn seeded(rule_number: u8) -> Automaton {
+        let mut config = vec![Cell::new(0); 41];
+        config[20] = Cell::new(1);
+        Automaton::new(Row::new(config), Box::new(WolframRule::new(rule_number)))
+    }
+
+    #[test]
+    fn test_sinks_side_by_side() {
+        let mut reference = seeded(30);
+        reference.evolve(30);
+
+        let mut automaton = seeded(30);
+        automaton.set_retention(Retention::Nothing);
+        let mut writer = WriterSink::new(Vec::new());
+        let mut statistics = StatisticsSink::default();
+        let mut generations = vec![];
+        let mut never = StopWhen::new(|generation, _: &Row| {
+            generations.push(generation);
+            false
+        });
+
+        let status = Pipeline::new().add(&mut writer).add(&mut statistics).add(&mut never).run(&mut automaton, 30);
+        assert_eq!(status, RunStatus::Completed { steps: 30 });
+        assert_eq!(generations, (0..=30).collect::<Vec<usize>>());
+        assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), reference.to_string());
+
+        let ones: usize = reference.grid().iter().map(|row| row.to_string().matches('#').count()).sum();
+        assert_eq!(statistics.rows(), 31);
+        assert_eq!(statistics.counts(), &[(31 * 41 - ones) as u64, ones as u64]);
+        assert_eq!(statistics.densities()[0], 1.0 / 41.0);
+    }
+
+    #[test]
+    fn test_stop_early() {
+        let mut automaton = seeded(90);
+        let mut statistics = StatisticsSink::default();
+        let mut wide = StopWhen::new(|_, row: &Row| row.to_string().matches('#').count() >= 8);
+        let mut pipeline = Pipeline::new();
+        pipeline.add(&mut statistics).add(&mut wide);
+
+        // La règle 90 donne 8 cellules vivantes pour la première fois à la génération 7.
+        assert_eq!(pipeline.run(&mut automaton, 100), RunStatus::Stopped { steps: 7, sink: 1 });
+        assert_eq!(automaton.iteration(), 8);
+        assert_eq!(pipeline.run(&mut automaton, 0), RunStatus::Completed { steps: 0 });
+        assert_eq!(statistics.rows(), 8);
+    }
+}
@@ -0,0 +1,317 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use crate::cell::Cell;
+use crate::row::Row;
+use crate::rules::Rule;
+
+/// Nombre maximal de configurations d'un graphe des transitions (`k^n`).
+const MAX_STATES: usize = 1 << 25;
+
+/// Cycle attracteur du graphe des transitions, avec son bassin d'attraction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attractor {
+    /// Configurations du cycle, dans l'ordre de l'évolution, à partir de la plus petite.
+    cycle: Vec<u32>,
+    basin_size: usize,
+    max_transient: usize,
+}
+
+impl Attractor {
+    /// Retourne les configurations du cycle, dans l'ordre de l'évolution, à partir de la plus petite.
+    pub fn cycle(&self) -> &[u32] {
+        &self.cycle
+    }
+
+    /// Retourne la période du cycle.
+    pub fn period(&self) -> usize {
+        self.cycle.len()
+    }
+
+    /// Retourne le nombre de configurations qui aboutissent au cycle, cycle compris.
+    pub fn basin_size(&self) -> usize {
+        self.basin_size
+    }
+
+    /// Retourne le plus grand nombre de générations nécessaires pour atteindre le cycle
+    /// depuis une configuration de son bassin.
+    pub fn max_transient(&self) -> usize {
+        self.max_transient
+    }
+}
+
+/// Graphe des transitions d'une règle sur l'anneau de `n` cellules : chaque configuration
+/// pointe vers la suivante.
+///
+/// Une configuration est numérotée comme un entier en base `k`, la cellule 0 étant le chiffre
+/// de poids fort. Chaque composante connexe du graphe est un bassin d'attraction : des arbres
+/// de configurations transitoires qui aboutissent à un cycle attracteur.
+///
+/// # Exemple
+/// ```text
+/// let graph = StateGraph::new(&WolframRule::new(110), 12).unwrap();
+/// for attractor in graph.attractors() {
+///     println!("période {}, bassin de {} configurations", attractor.period(), attractor.basin_size());
+/// }
+/// std::fs::write("rule110.dot", graph.to_dot())?;
+/// ```
+pub struct StateGraph {
+    len: usize,
+    colours: u8,
+    successors: Vec<u32>,
+    attractors: Vec<Attractor>,
+    /// Indice de l'attracteur de chaque configuration.
+    basins: Vec<u32>,
+    /// Nombre de générations avant d'atteindre le cycle, pour chaque configuration.
+    transients: Vec<u32>,
+}
+
+impl StateGraph {
+    /// Construit le graphe des transitions d'une règle sur l'anneau de `len` cellules,
+    /// ou retourne `None` si la règle n'est pas déterministe.
+    ///
+    /// # Panics
+    /// Si l'anneau a plus de 2^25 configurations (25 cellules binaires).
+    pub fn new(rule: &dyn Rule, len: usize) -> Option<StateGraph> {
+        let table = rule.lookup_table()?;
+        let k = rule.colours() as usize;
+        let count = k
+            .checked_pow(len as u32)
+            .filter(|&count| count <= MAX_STATES)
+            .expect("StateGraph: too many configurations");
+
+        let (window, left_reach) = (rule.window(), rule.left_reach());
+        let mut states = vec![0usize; len];
+        let successors = (0..count)
+            .map(|configuration| {
+                let mut rest = configuration;
+                for state in states.iter_mut().rev() {
+                    *state = rest % k;
+                    rest /= k;
+                }
+                (0..len).fold(0, |next, i| {
+                    let index = (0..window).fold(0, |index, j| index * k + states[(i + len * window + j - left_reach) % len]);
+                    next * k as u32 + table[index] as u32
+                })
+            })
+            .collect();
+
+        let mut graph = Self {
+            len,
+            colours: rule.colours(),
+            successors,
+            attractors: vec![],
+            basins: vec![],
+            transients: vec![],
+        };
+        graph.find_attractors();
+        Some(graph)
+    }
+
+    /// Suit l'évolution de chaque configuration jusqu'à une configuration déjà classée,
+    /// ou jusqu'à un cycle encore inconnu.
+    fn find_attractors(&mut self) {
+        const UNKNOWN: u32 = u32::MAX;
+        let count = self.successors.len();
+        self.basins = vec![UNKNOWN; count];
+        self.transients = vec![0; count];
+        // Rang de chaque configuration sur le chemin en cours, pour reconnaître un nouveau cycle.
+        let mut visits = vec![UNKNOWN; count];
+        let mut path = vec![];
+
+        for start in 0..count {
+            if self.basins[start] != UNKNOWN {
+                continue;
+            }
+            path.clear();
+            let mut state = start;
+            while self.basins[state] == UNKNOWN && visits[state] == UNKNOWN {
+                visits[state] = path.len() as u32;
+                path.push(state);
+                state = self.successors[state] as usize;
+            }
+
+            let (basin, mut transient, transients) = if self.basins[state] == UNKNOWN {
+                // Nouveau cycle : il commence au rang de la configuration retrouvée.
+                let basin = self.attractors.len() as u32;
+                let cycle = path.split_off(visits[state] as usize);
+                for &state in &cycle {
+                    self.basins[state] = basin;
+                }
+                let first = cycle.iter().enumerate().min_by_key(|&(_, &state)| state).unwrap().0;
+                let mut cycle: Vec<u32> = cycle.into_iter().map(|state| state as u32).collect();
+                cycle.rotate_left(first);
+                self.attractors.push(Attractor { basin_size: cycle.len(), cycle, max_transient: 0 });
+                (basin, 0, &path[..])
+            } else {
+                (self.basins[state], self.transients[state], &path[..])
+            };
+
+            for &state in transients.iter().rev() {
+                transient += 1;
+                self.basins[state] = basin;
+                self.transients[state] = transient;
+            }
+            let attractor = &mut self.attractors[basin as usize];
+            attractor.basin_size += transients.len();
+            attractor.max_transient = attractor.max_transient.max(transient as usize);
+        }
+    }
+
+    /// Retourne le nombre de cellules de l'anneau.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Retourne `true` si l'anneau n'a aucune cellule.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Retourne le nombre de configurations de l'anneau, `k^n`.
+    pub fn state_count(&self) -> usize {
+        self.successors.len()
+    }
+
+    /// Retourne la configuration qui suit `state`.
+    pub fn successor(&self, state: u32) -> u32 {
+        self.successors[state as usize]
+    }
+
+    /// Retourne les cycles attracteurs, dans l'ordre de la plus petite configuration de leur bassin.
+    pub fn attractors(&self) -> &[Attractor] {
+        &self.attractors
+    }
+
+    /// Retourne l'indice, dans [`StateGraph::attractors`], de l'attracteur auquel aboutit `state`.
+    pub fn attractor_of(&self, state: u32) -> usize {
+        self.basins[state as usize] as usize
+    }
+
+    /// Retourne le nombre de générations nécessaires pour que `state` atteigne son cycle.
+    pub fn transient(&self, state: u32) -> usize {
+        self.transients[state as usize] as usize
+    }
+
+    /// Retourne les configurations « Jardin d'Éden », qui n'ont pas d'antécédent.
+    pub fn gardens_of_eden(&self) -> Vec<u32> {
+        let mut reached = vec![false; self.state_count()];
+        for &next in &self.successors {
+            reached[next as usize] = true;
+        }
+        (0..self.state_count() as u32).filter(|&state| !reached[state as usize]).collect()
+    }
+
+    /// Retourne la ligne d'une configuration.
+    pub fn row(&self, state: u32) -> Row {
+        let k = self.colours as u32;
+        let mut rest = state;
+        let mut cells = vec![Cell::new(0); self.len];
+        for cell in cells.iter_mut().rev() {
+            *cell = Cell::new((rest % k) as u8);
+            rest /= k;
+        }
+        Row::new(cells)
+    }
+
+    /// Retourne le numéro de la configuration d'une ligne.
+    ///
+    /// # Panics
+    /// Si la ligne n'a pas la longueur de l'anneau ou contient un état inconnu de la règle.
+    pub fn state(&self, row: &Row) -> u32 {
+        assert_eq!(row.len(), self.len, "StateGraph: the row should have the length of the ring");
+        (0..row.len()).fold(0, |state, i| {
+            let cell = row.get(i).unwrap().state();
+            assert!(cell < self.colours, "StateGraph: state {} is not a state of the rule", cell);
+            state * self.colours as u32 + cell as u32
+        })
+    }
+
+    /// Retourne le graphe au format DOT de Graphviz : un sommet par configuration, étiqueté par
+    /// sa ligne, et une arête vers la configuration suivante. Les configurations des cycles
+    /// sont encadrées.
+    ///
+    /// Le rendu n'est lisible que pour de petits anneaux (une dizaine de cellules binaires).
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph states {\n    node [shape=plaintext, fontname=\"monospace\"];\n");
+        for state in 0..self.state_count() as u32 {
+            let frame = if self.transient(state) == 0 { ", shape=box" } else { "" };
+            writeln!(dot, "    {} [label=\"{}\"{}];", state, self.row(state).to_string(), frame).unwrap();
+        }
+        for (state, next) in self.successors.iter().enumerate() {
+            writeln!(dot, "    {} -> {};", state, next).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Écrit le graphe au format DOT (voir [`StateGraph::to_dot`]).
+    pub fn write_dot<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_dot().as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::*;
+    use crate::automaton::Automaton;
+    use crate::natural::Natural;
+    use crate::rules::{TotalisticRule, WolframRule};
+
+    #[test]
+    fn test_matches_automaton() {
+        let rules: Vec<(Box<dyn Rule>, usize)> = vec![
+            (Box::new(WolframRule::new(110)), 9),
+            (Box::new(WolframRule::new(30)), 8),
+            (Box::new(WolframRule::with_window(0x5A3C, 4)), 7),
+            (Box::new(TotalisticRule::new(&Natural::from(1599u64), 3, 3)), 5),
+        ];
+
+        for (rule, len) in &rules {
+            let graph = StateGraph::new(rule.as_ref(), *len).unwrap();
+            let mut basin_sizes = vec![0; graph.attractors().len()];
+            for state in 0..graph.state_count() as u32 {
+                assert_eq!(graph.state(&graph.row(state)), state);
+
+                // Évolution jusqu'à la première configuration déjà vue.
+                let mut automaton = Automaton::new(graph.row(state), Box::new(rule.info().unwrap().to_rule()));
+                let mut seen = HashMap::new();
+                while !seen.contains_key(&automaton.current().to_string()) {
+                    seen.insert(automaton.current().to_string(), automaton.iteration() - 1);
+                    automaton.next();
+                }
+                let first = seen[&automaton.current().to_string()];
+                let attractor = &graph.attractors()[graph.attractor_of(state)];
+                assert_eq!(graph.transient(state), first);
+                assert_eq!(attractor.period(), automaton.iteration() - 1 - first);
+                assert!(attractor.cycle().contains(&graph.state(automaton.current())));
+                assert!(graph.transient(state) <= attractor.max_transient());
+                basin_sizes[graph.attractor_of(state)] += 1;
+            }
+            let expected: Vec<usize> = graph.attractors().iter().map(Attractor::basin_size).collect();
+            assert_eq!(basin_sizes, expected);
+        }
+    }
+
+    #[test]
+    fn test_basins() {
+        let graph = StateGraph::new(&WolframRule::new(204), 6).unwrap();
+        assert_eq!(graph.attractors().len(), 64);
+        assert!(graph.gardens_of_eden().is_empty());
+
+        let graph = StateGraph::new(&WolframRule::new(0), 2).unwrap();
+        assert_eq!(graph.attractors(), &[Attractor { cycle: vec![0], basin_size: 4, max_transient: 1 }]);
+        assert_eq!(graph.gardens_of_eden(), vec![1, 2, 3]);
+        assert_eq!(
+            graph.to_dot(),
+            "digraph states {\n    node [shape=plaintext, fontname=\"monospace\"];\n    \
+             0 [label=\"..\", shape=box];\n    1 [label=\".#\"];\n    2 [label=\"#.\"];\n    3 [label=\"##\"];\n    \
+             0 -> 0;\n    1 -> 0;\n    2 -> 0;\n    3 -> 0;\n}\n"
+        );
+
+        // Sur 4 cellules, la règle 90 envoie toute configuration sur 0 en 2 générations au plus.
+        let graph = StateGraph::new(&WolframRule::new(90), 4).unwrap();
+        assert_eq!(graph.attractors().len(), 1);
+        assert_eq!(graph.attractors()[0].max_transient(), 2);
+    }
+}
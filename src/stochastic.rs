@@ -0,0 +1,187 @@
+use std::cell::RefCell;
+use crate::random::Random;
+use crate::rules::Rule;
+
+/// Règle probabiliste binaire : chaque voisinage donne la probabilité que la cellule passe à 1.
+///
+/// Le voisinage, lu de gauche à droite, est interprété comme un entier binaire (comme pour
+/// `WolframRule`) qui indexe le vecteur des probabilités. Le générateur est interne à la règle :
+/// deux évolutions lancées avec la même graine donnent exactement les mêmes lignes.
+pub struct ProbabilisticRule {
+    probabilities: Vec<f64>,
+    window: usize,
+    random: RefCell<Random>,
+}
+
+impl ProbabilisticRule {
+    /// Crée une règle probabiliste sur un voisinage de `window` cellules.
+    ///
+    /// `probabilities` contient `2^window` probabilités, une par voisinage.
+    ///
+    /// # Exemple
+    /// ```text
+    /// // Règle 30 dont les transitions vers 1 n'ont lieu qu'une fois sur deux.
+    /// let probabilities = (0..8).map(|i| if (30 >> i) & 1 == 1 { 0.5 } else { 0.0 }).collect();
+    /// let rule = ProbabilisticRule::new(probabilities, 3, 42);
+    /// ```
+    pub fn new(probabilities: Vec<f64>, window: usize, seed: u64) -> Self {
+        assert_eq!(probabilities.len(), 1 << window, "ProbabilisticRule: one probability per neighbourhood is needed");
+        assert!(probabilities.iter().all(|p| (0.0..=1.0).contains(p)), "ProbabilisticRule: probabilities should be in [0, 1]");
+
+        Self {
+            probabilities,
+            window,
+            random: RefCell::new(Random::new(seed)),
+        }
+    }
+
+    /// Crée une règle qui applique la règle élémentaire `second` avec probabilité `p`
+    /// et la règle élémentaire `first` sinon (automate « diploïde »).
+    pub fn diploid(first: u8, second: u8, p: f64, seed: u64) -> Self {
+        let probabilities = (0..8)
+            .map(|i| {
+                let a = ((first >> i) & 1) as f64;
+                let b = ((second >> i) & 1) as f64;
+                (1.0 - p) * a + p * b
+            })
+            .collect();
+        Self::new(probabilities, 3, seed)
+    }
+}
+
+impl Rule for ProbabilisticRule {
+    fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        let index = neighbourhood.iter().fold(0, |index, &state| (index << 1) | state as usize);
+        self.random.borrow_mut().bernoulli(self.probabilities[index]) as u8
+    }
+
+    fn window(&self) -> usize {
+        self.window
+    }
+
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+}
+
+/// Couche de bruit appliquée après une règle : chaque nouvel état est altéré avec probabilité `p`.
+///
+/// Pour une règle binaire, l'état est inversé. Pour `k` états, il est remplacé par
+/// l'un des `k - 1` autres états, tiré uniformément.
+pub struct NoisyRule {
+    rule: Box<dyn Rule>,
+    p: f64,
+    random: RefCell<Random>,
+}
+
+impl NoisyRule {
+    /// Ajoute du bruit de probabilité `p` à une règle.
+    ///
+    /// # Exemple
+    /// ```text
+    /// let rule = NoisyRule::new(Box::new(WolframRule::new(184)), 0.01, 42);
+    /// ```
+    pub fn new(rule: Box<dyn Rule>, p: f64, seed: u64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "NoisyRule: probability should be in [0, 1]");
+        Self {
+            rule,
+            p,
+            random: RefCell::new(Random::new(seed)),
+        }
+    }
+}
+
+impl Rule for NoisyRule {
+    fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        let state = self.rule.apply(neighbourhood);
+        let mut random = self.random.borrow_mut();
+        if !random.bernoulli(self.p) {
+            return state;
+        }
+
+        let k = self.rule.colours() as u64;
+        ((state as u64 + 1 + random.below(k - 1)) % k) as u8
+    }
+
+    fn window(&self) -> usize {
+        self.rule.window()
+    }
+
+    fn left_reach(&self) -> usize {
+        self.rule.left_reach()
+    }
+
+    fn right_reach(&self) -> usize {
+        self.rule.right_reach()
+    }
+
+    fn colours(&self) -> u8 {
+        self.rule.colours()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.p == 0.0 && self.rule.is_deterministic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::Automaton;
+    use crate::cell::Cell;
+    use crate::row::Row;
+    use crate::rules::WolframRule;
+
+    fn seed_row() -> Row {
+        let mut config = vec![Cell::new(0); 101];
+        config[50] = Cell::new(1);
+        Row::new(config)
+    }
+
+    fn run(rule: Box<dyn Rule>, steps: u64) -> String {
+        let mut automaton = Automaton::new(seed_row(), rule);
+        automaton.evolve(steps);
+        automaton.to_string()
+    }
+
+    #[test]
+    fn test_reproducible_from_seed() {
+        let diploid = |seed| Box::new(ProbabilisticRule::diploid(30, 90, 0.3, seed));
+        assert_eq!(run(diploid(1), 40), run(diploid(1), 40));
+        assert_ne!(run(diploid(1), 40), run(diploid(2), 40));
+
+        let noisy = |seed| Box::new(NoisyRule::new(Box::new(WolframRule::new(110)), 0.05, seed));
+        assert_eq!(run(noisy(3), 40), run(noisy(3), 40));
+        assert_ne!(run(noisy(3), 40), run(noisy(4), 40));
+    }
+
+    #[test]
+    fn test_degenerate_probabilities() {
+        let expected = run(Box::new(WolframRule::new(30)), 40);
+        assert_eq!(run(Box::new(ProbabilisticRule::diploid(30, 90, 0.0, 5)), 40), expected);
+        assert_eq!(run(Box::new(ProbabilisticRule::diploid(90, 30, 1.0, 5)), 40), expected);
+        assert_eq!(run(Box::new(NoisyRule::new(Box::new(WolframRule::new(30)), 0.0, 5)), 40), expected);
+    }
+
+    #[test]
+    fn test_noise_flips_states() {
+        let row = Row::new(vec![Cell::new(0); 64]);
+        let always = NoisyRule::new(Box::new(WolframRule::new(0)), 1.0, 9);
+        assert_eq!(row.next(&always).to_string(), "#".repeat(64));
+
+        let three = WolframRule::with_colours(&0u64.into(), 3, 3);
+        let noisy = NoisyRule::new(Box::new(three), 1.0, 9);
+        let next = Row::new(vec![Cell::new(0); 1000]).next(&noisy);
+        let twos = (0..next.len()).filter(|&i| next.get(i).unwrap().state() == 2).count();
+        assert!((0..next.len()).all(|i| next.get(i).unwrap().state() != 0));
+        assert!((400..600).contains(&twos));
+    }
+
+    #[test]
+    fn test_probability_frequency() {
+        let rule = ProbabilisticRule::new(vec![0.25, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], 3, 11);
+        let next = Row::new(vec![Cell::new(0); 10_000]).next(&rule);
+        let ones = next.to_string().matches('#').count();
+        assert!((2_300..2_700).contains(&ones));
+    }
+}
@@ -0,0 +1,220 @@
+use crate::boundary::{Boundary, Side};
+use crate::cell::Cell;
+use crate::random::Random;
+use crate::row::Row;
+use crate::rules::Rule;
+
+/// Sens de parcours d'un balayage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Ordre de mise à jour des cellules à chaque itération.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum UpdateScheme {
+    /// Toutes les cellules sont mises à jour en même temps.
+    #[default]
+    Synchronous,
+    /// Les cellules sont mises à jour une à une, dans un ordre fixe : chaque cellule
+    /// voit l'état déjà mis à jour des cellules qui la précèdent.
+    Sweep(Order),
+    /// Les cellules sont mises à jour une à une, selon une nouvelle permutation
+    /// aléatoire à chaque itération.
+    RandomPermutation { seed: u64 },
+    /// Chaque cellule est mise à jour avec probabilité `alpha`, indépendamment des autres
+    /// (α-asynchronisme) ; les cellules choisies sont mises à jour en même temps.
+    Alpha { alpha: f64, seed: u64 },
+    /// Chaque cellule possède une horloge de période tirée dans `[1, max_period]` et de
+    /// phase aléatoire ; elle n'est mise à jour qu'aux tops de son horloge.
+    Clocked { max_period: u32, seed: u64 },
+}
+
+/// Exécute un schéma de mise à jour non synchrone, avec son générateur et ses horloges.
+pub(crate) struct Scheduler {
+    scheme: UpdateScheme,
+    random: Random,
+    /// Période et phase de l'horloge de chaque cellule (schéma `Clocked`).
+    clocks: Vec<(u32, u32)>,
+}
+
+impl Scheduler {
+    /// Retourne `None` pour le schéma synchrone, qui n'a pas besoin d'ordonnanceur.
+    pub(crate) fn new(scheme: UpdateScheme) -> Option<Scheduler> {
+        let seed = match &scheme {
+            UpdateScheme::Synchronous => return None,
+            UpdateScheme::Sweep(_) => 0,
+            UpdateScheme::RandomPermutation { seed } => *seed,
+            UpdateScheme::Alpha { alpha, seed } => {
+                assert!((0.0..=1.0).contains(alpha), "UpdateScheme: alpha should be in [0, 1]");
+                *seed
+            }
+            UpdateScheme::Clocked { max_period, seed } => {
+                assert!(*max_period > 0, "UpdateScheme: clock periods should be strictly positive");
+                *seed
+            }
+        };
+
+        Some(Self {
+            scheme,
+            random: Random::new(seed),
+            clocks: vec![],
+        })
+    }
+
+    /// Calcule la ligne suivante selon le schéma de mise à jour.
+    pub(crate) fn next_row(&mut self, row: &Row, rule: &dyn Rule, boundary: &Boundary) -> Row {
+        let len = row.len();
+        match self.scheme {
+            UpdateScheme::Synchronous => row.next_with_boundary(rule, boundary),
+            UpdateScheme::Sweep(Order::LeftToRight) => next_sequential(row, rule, boundary, 0..len),
+            UpdateScheme::Sweep(Order::RightToLeft) => next_sequential(row, rule, boundary, (0..len).rev()),
+            UpdateScheme::RandomPermutation { .. } => {
+                let mut order: Vec<usize> = (0..len).collect();
+                self.random.shuffle(&mut order);
+                next_sequential(row, rule, boundary, order)
+            }
+            UpdateScheme::Alpha { alpha, .. } => {
+                let mask: Vec<bool> = (0..len).map(|_| self.random.bernoulli(alpha)).collect();
+                next_masked(row, rule, boundary, &mask)
+            }
+            UpdateScheme::Clocked { max_period, .. } => {
+                while self.clocks.len() < len {
+                    let period = 1 + self.random.below(max_period as u64) as u32;
+                    let phase = self.random.below(period as u64) as u32;
+                    self.clocks.push((period, phase));
+                }
+
+                let time = row.iteration() as u64;
+                let mask: Vec<bool> = self.clocks[..len]
+                    .iter()
+                    .map(|&(period, phase)| (time + phase as u64).is_multiple_of(period as u64))
+                    .collect();
+                next_masked(row, rule, boundary, &mask)
+            }
+        }
+    }
+}
+
+/// Met à jour les cellules une à une dans l'ordre donné, sur place.
+fn next_sequential(row: &Row, rule: &dyn Rule, boundary: &Boundary, order: impl IntoIterator<Item = usize>) -> Row {
+    let len = row.len();
+    let (left, right) = (rule.left_reach() as isize, rule.right_reach() as isize);
+    let mut states: Vec<u8> = (0..len).map(|i| row.get(i).unwrap().state()).collect();
+    let mut neighbourhood = Vec::with_capacity(rule.window());
+
+    for i in order {
+        neighbourhood.clear();
+        for offset in -left..=right {
+            let j = i as isize + offset;
+            let state = if j < 0 {
+                boundary.ghost(Side::Left, (-j) as usize, len, row.iteration(), |k| states[k])
+            } else if j as usize >= len {
+                boundary.ghost(Side::Right, j as usize - len + 1, len, row.iteration(), |k| states[k])
+            } else {
+                states[j as usize]
+            };
+            neighbourhood.push(state);
+        }
+        states[i] = rule.apply(&neighbourhood);
+    }
+
+    let mut next = Row::new(states.into_iter().map(Cell::new).collect());
+    next.set_iteration(row.iteration() + 1);
+    next
+}
+
+/// Met à jour en même temps les cellules dont le masque est vrai ; les autres sont inchangées.
+fn next_masked(row: &Row, rule: &dyn Rule, boundary: &Boundary, mask: &[bool]) -> Row {
+    let image = row.next_with_boundary(rule, boundary);
+    let configuration = (0..row.len())
+        .map(|i| if mask[i] { image.get(i) } else { row.get(i) }.unwrap().clone())
+        .collect();
+
+    let mut next = Row::new(configuration);
+    next.set_iteration(row.iteration() + 1);
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::Automaton;
+    use crate::rules::WolframRule;
+
+    fn row_from_binary(binary: &str) -> Row {
+        Row::new(binary.chars().map(|c| Cell::new(c.to_digit(10).unwrap() as u8)).collect())
+    }
+
+    fn run(scheme: UpdateScheme, rule_number: u8, first: &str, boundary: Boundary, steps: u64) -> String {
+        let mut automaton = Automaton::new(row_from_binary(first), Box::new(WolframRule::new(rule_number)));
+        automaton.set_boundary(boundary).set_update_scheme(scheme);
+        automaton.evolve(steps);
+        automaton.to_string()
+    }
+
+    #[test]
+    fn test_sweeps() {
+        let last = |s: String| s.lines().last().unwrap().to_string();
+        assert_eq!(last(run(UpdateScheme::Synchronous, 90, "01000", Boundary::Fixed(0), 1)), "#.#..");
+        assert_eq!(last(run(UpdateScheme::Sweep(Order::LeftToRight), 90, "01000", Boundary::Fixed(0), 1)), "#####");
+        assert_eq!(last(run(UpdateScheme::Sweep(Order::RightToLeft), 90, "01000", Boundary::Fixed(0), 1)), "###..");
+    }
+
+    #[test]
+    fn test_random_permutation_updates_each_cell_once() {
+        // La règle 51 inverse la cellule : quel que soit l'ordre, la ligne est complémentée.
+        let first = "0110100111010100101";
+        let result = run(UpdateScheme::RandomPermutation { seed: 3 }, 51, first, Boundary::Periodic, 1);
+        assert_eq!(result.lines().last().unwrap(), "#..#.##...#.#.##.#.");
+
+        let a = run(UpdateScheme::RandomPermutation { seed: 3 }, 30, first, Boundary::Periodic, 30);
+        let b = run(UpdateScheme::RandomPermutation { seed: 3 }, 30, first, Boundary::Periodic, 30);
+        let c = run(UpdateScheme::RandomPermutation { seed: 4 }, 30, first, Boundary::Periodic, 30);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_alpha() {
+        let first = "0001000000010000001";
+        let synchronous = run(UpdateScheme::Synchronous, 30, first, Boundary::Periodic, 20);
+        assert_eq!(run(UpdateScheme::Alpha { alpha: 1.0, seed: 1 }, 30, first, Boundary::Periodic, 20), synchronous);
+
+        let frozen = run(UpdateScheme::Alpha { alpha: 0.0, seed: 1 }, 30, first, Boundary::Periodic, 5);
+        assert!(frozen.lines().all(|line| line == "...#.......#......#"));
+
+        let a = run(UpdateScheme::Alpha { alpha: 0.5, seed: 8 }, 30, first, Boundary::Periodic, 20);
+        assert_eq!(a, run(UpdateScheme::Alpha { alpha: 0.5, seed: 8 }, 30, first, Boundary::Periodic, 20));
+        assert_ne!(a, synchronous);
+    }
+
+    #[test]
+    fn test_clocked() {
+        let first = "0001000000010000001";
+        let synchronous = run(UpdateScheme::Synchronous, 110, first, Boundary::Periodic, 20);
+        assert_eq!(run(UpdateScheme::Clocked { max_period: 1, seed: 1 }, 110, first, Boundary::Periodic, 20), synchronous);
+
+        let a = run(UpdateScheme::Clocked { max_period: 3, seed: 2 }, 110, first, Boundary::Periodic, 20);
+        assert_eq!(a, run(UpdateScheme::Clocked { max_period: 3, seed: 2 }, 110, first, Boundary::Periodic, 20));
+        assert_ne!(a, synchronous);
+
+        let mut scheduler = Scheduler::new(UpdateScheme::Clocked { max_period: 4, seed: 5 }).unwrap();
+        let rule = WolframRule::new(51);
+        let mut row = row_from_binary(first);
+        let mut updates = vec![0; row.len()];
+        for _ in 0..12 {
+            let next = scheduler.next_row(&row, &rule, &Boundary::Periodic);
+            for (i, count) in updates.iter_mut().enumerate() {
+                if next.get(i) != row.get(i) {
+                    *count += 1;
+                }
+            }
+            row = next;
+        }
+        for (i, &(period, _)) in scheduler.clocks.iter().enumerate() {
+            assert_eq!(updates[i], 12 / period as usize);
+        }
+    }
+}